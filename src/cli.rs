@@ -0,0 +1,852 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+use log::{error, info};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::{
+    tipp10::{
+        self, backup,
+        csv::{self, CsvError, CsvField, RowError},
+        validation::{self, Severity},
+        CatalogueLesson, IdMode, Language, Lesson, LessonCatalogue, LessonType, SQLiteError,
+        Session,
+    },
+    tipp10w::Tipp10W,
+};
+
+/// Usage text printed by `help` and on wrong input.
+pub const USAGE: &str = "\
+Usage: tipp10w [global options] [<command> <tipp10-path> [arguments]]
+
+Without a command the terminal user interface is started. Global options can be given
+anywhere and apply to the user interface and every command.
+
+Global options:
+  -d, --debug                     Write a log to debug.log
+  --stable-ids                    Do not renumber the lesson IDs after deleting a lesson
+  --backups <n>                   Number of backups in portable/backups that are kept
+                                  (default: 5, 0 disables backups). The database is
+                                  backed up before the first change
+  --language <en|de|fr|es>        Lesson names of new or changed lessons
+                                  (default: the lesson list of the database)
+  --max-cpm <n>                   Warn about lessons faster than this (default: 1000)
+  --max-duration <duration>       Warn about lessons longer than this (default: 4:00:00)
+  --max-error-rate <n>            Warn about lessons with an error rate above this percent
+                                  (default: 50)
+  --allow-future                  Accept timestamps in the future. Lessons with more errors
+                                  than strokes or strokes without a duration are always
+                                  rejected
+
+Commands:
+  list   <tipp10-path> [--format <f>]    List all lessons as text or json
+  add    <tipp10-path> [options]         Append a new lesson
+  update <tipp10-path> <id> [options]    Update the lesson with the given ID
+  delete <tipp10-path> <id>              Delete the lesson with the given ID
+  export <tipp10-path> [<file>]          Export all lessons as CSV to a file or stdout
+  import <tipp10-path> <file> [import options]
+                                         Import lessons from a CSV file or - for stdin
+  compact <tipp10-path>                  Renumber the lesson IDs consecutively from 0
+  backups <tipp10-path>                  List the backups of the database
+  restore <tipp10-path> <backup>         Restore a backup given by its file name or path
+  help                                   Print this help
+
+Options:
+  --format <text|json>            Output format of list (default: text)
+  --lesson <n>                    Lesson number of the lesson list (add default: 18)
+  --strokes <n>                   Number of strokes (add default: tokens or 0)
+  --errors <n>                    Number of errors (add default: 0)
+  --tokens <n>                    Number of characters of the text (add default: strokes)
+  --duration <duration>           Duration like 600, 10:00, 1:05:00 or 10m
+                                  (add default: 10:00)
+  --timestamp <date>              Timestamp like 20240305143000, 2024-03-05T14:30,
+                                  yesterday or -2d (add default: now)
+  --type <training|open-text|own> Lesson type (add default: training)
+
+Import options:
+  --map <column>=<field>          Read a CSV column as id, lesson, name, user_lesson,
+                                  timestamp, duration, strokes, errors, tokens or type
+  --all-or-nothing                Import nothing if any row is invalid
+
+Exit codes: 0 success, 1 database error, 2 wrong usage";
+
+/// Exit code for errors while working with the database.
+const EXIT_FAILURE: u8 = 1;
+/// Exit code for wrong command line usage.
+const EXIT_USAGE: u8 = 2;
+
+/// A non-interactive command given on the command line.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List(OutputFormat),
+    Add(LessonArgs),
+    Update(usize, LessonArgs),
+    Delete(usize),
+    Export(Option<String>),
+    Import(ImportArgs),
+    Compact,
+    Backups,
+    Restore(String),
+    Help,
+}
+
+/// Arguments of the import command.
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportArgs {
+    pub file: String,
+    pub mapping: Vec<(String, CsvField)>,
+    pub all_or_nothing: bool,
+}
+
+/// Output format of the list command.
+#[derive(Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+impl OutputFormat {
+    /// Get the output format from its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Lesson fields given on the command line. Fields that are None are left unchanged or defaulted.
+#[derive(Debug, Default, PartialEq)]
+pub struct LessonArgs {
+    pub lesson: Option<usize>,
+    pub strokes: Option<usize>,
+    pub errors: Option<usize>,
+    pub tokens: Option<usize>,
+    pub duration: Option<usize>,
+    pub timestamp: Option<u64>,
+    pub lesson_type: Option<LessonType>,
+}
+
+/// Options that apply to the user interface and every command. They can be given anywhere.
+#[derive(Debug, Default, PartialEq)]
+pub struct GlobalOptions {
+    /// Write the log to debug.log.
+    pub debug: bool,
+    /// The session with the ID mode, backups, language and validation rules of the options.
+    pub session: Session,
+}
+
+/// A parsed command line invocation.
+#[derive(Debug, PartialEq)]
+pub struct Cli {
+    pub path: String,
+    pub command: Command,
+}
+
+/// Errors that can occur while running a command.
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    SQLite(SQLiteError),
+    Csv(CsvError),
+    PartialImport(Vec<RowError>),
+    Io(io::Error),
+}
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(e) => write!(f, "{}", e),
+            CliError::SQLite(e) => write!(f, "{}", e),
+            CliError::Csv(e) => write!(f, "{}", e),
+            CliError::PartialImport(errors) => {
+                write!(f, "{} rows could not be imported:", errors.len())?;
+                for e in errors {
+                    write!(f, "\n{}", e)?;
+                }
+
+                Ok(())
+            }
+            CliError::Io(e) => write!(f, "Io Error: {}", e),
+        }
+    }
+}
+impl From<SQLiteError> for CliError {
+    fn from(e: SQLiteError) -> Self {
+        CliError::SQLite(e)
+    }
+}
+impl From<rusqlite::Error> for CliError {
+    fn from(e: rusqlite::Error) -> Self {
+        CliError::SQLite(SQLiteError::RusqliteError(e))
+    }
+}
+impl From<CsvError> for CliError {
+    fn from(e: CsvError) -> Self {
+        CliError::Csv(e)
+    }
+}
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+impl CliError {
+    /// Get the process exit code for the error.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            CliError::Usage(_) => ExitCode::from(EXIT_USAGE),
+            _ => ExitCode::from(EXIT_FAILURE),
+        }
+    }
+}
+
+impl Cli {
+    /// Parse the command line arguments without the program name. Returns the global options and
+    /// None if no command was given and the user interface should be started.
+    pub fn parse(args: &[String]) -> Result<(GlobalOptions, Option<Self>), CliError> {
+        let (options, args) = parse_global_options(args)?;
+        let cli = Self::parse_command(&args)?;
+
+        Ok((options, cli))
+    }
+
+    /// Parse the command and its arguments. None if no command was given.
+    fn parse_command(args: &[String]) -> Result<Option<Self>, CliError> {
+        let mut args = args.iter();
+        let command = match args.next() {
+            Some(command) => command.as_str(),
+            None => return Ok(None),
+        };
+
+        if matches!(command, "help" | "-h" | "--help") {
+            return Ok(Some(Self {
+                path: String::new(),
+                command: Command::Help,
+            }));
+        };
+
+        let path = match args.next() {
+            Some(path) => path.clone(),
+            None => return Err(CliError::Usage("Missing Tipp10 path!".to_string())),
+        };
+
+        let command = match command {
+            "list" => Command::List(parse_format(&mut args)?),
+            "add" => Command::Add(parse_lesson_args(&mut args)?),
+            "update" => {
+                let id = parse_id(args.next())?;
+                Command::Update(id, parse_lesson_args(&mut args)?)
+            }
+            "delete" => Command::Delete(parse_id(args.next())?),
+            "export" => Command::Export(args.next().cloned()),
+            "import" => Command::Import(parse_import_args(&mut args)?),
+            "compact" => Command::Compact,
+            "backups" => Command::Backups,
+            "restore" => match args.next() {
+                Some(backup) => Command::Restore(backup.clone()),
+                None => return Err(CliError::Usage("Missing backup!".to_string())),
+            },
+            command => {
+                return Err(CliError::Usage(format!("Unknown command: {}", command)));
+            }
+        };
+
+        // Every argument has to be consumed
+        if let Some(arg) = args.next() {
+            return Err(CliError::Usage(format!("Unexpected argument: {}", arg)));
+        };
+
+        Ok(Some(Self { path, command }))
+    }
+
+    /// Open the database and run the command. Returns the exit code of the process.
+    pub fn run(&self, mut session: Session) -> ExitCode {
+        let result = if self.command == Command::Help {
+            println!("{}", USAGE);
+            Ok(())
+        } else {
+            open_db(&self.path).and_then(|mut conn| {
+                execute(&mut conn, &self.command, &mut session, &mut io::stdout())
+            })
+        };
+
+        match result {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(e) => {
+                error!("Command failed! Error: {}", e);
+                eprintln!("Error: {}", e);
+                if let CliError::Usage(_) = e {
+                    eprintln!("\n{}", USAGE);
+                };
+
+                e.exit_code()
+            }
+        }
+    }
+}
+
+/// Print the warnings of the validation rules about the written lesson.
+fn print_warnings(lesson: &Lesson, session: &Session) {
+    let now = tipp10::get_timestamp();
+    for issue in validation::validate_lesson(lesson, &session.validation_rules, now) {
+        if issue.severity == Severity::Warning {
+            eprintln!("{}", issue);
+        };
+    }
+}
+
+/// Open the Tipp10 database without creating it if it does not exist.
+fn open_db(path: &str) -> Result<Connection, CliError> {
+    let path = Tipp10W::get_path_to_db(path);
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+/// Run a command on an open database and write the output to out.
+pub fn execute(
+    conn: &mut Connection,
+    command: &Command,
+    session: &mut Session,
+    out: &mut impl Write,
+) -> Result<(), CliError> {
+    match command {
+        Command::List(OutputFormat::Text) => {
+            for lesson in tipp10::get_lessons(conn)? {
+                writeln!(out, "{}", lesson)?;
+            }
+        }
+        Command::List(OutputFormat::Json) => {
+            let lessons = tipp10::get_lessons(conn)?;
+            writeln!(out, "[")?;
+            for (i, lesson) in lessons.iter().enumerate() {
+                let separator = if i + 1 < lessons.len() { "," } else { "" };
+                writeln!(out, "  {}{}", lesson.to_json(), separator)?;
+            }
+            writeln!(out, "]")?;
+        }
+        Command::Add(lesson_args) => {
+            let catalogue = LessonCatalogue::load(conn, session.language);
+            let catalogue_lesson = match lesson_args.lesson {
+                Some(number) => get_catalogue_lesson(&catalogue, number)?,
+                None => catalogue.get_default(),
+            };
+            let lesson = Lesson::new(
+                0,
+                catalogue_lesson,
+                lesson_args.timestamp.unwrap_or_else(tipp10::get_timestamp),
+                lesson_args.duration.unwrap_or(600),
+                // If only one of strokes and tokens is given the other one is the same
+                lesson_args.strokes.or(lesson_args.tokens).unwrap_or(0),
+                lesson_args.errors.unwrap_or(0),
+            )
+            .with_tokens(lesson_args.tokens.or(lesson_args.strokes).unwrap_or(0))
+            .with_lesson_type(lesson_args.lesson_type.unwrap_or_default());
+            let id = tipp10::append_lesson(conn, session, &lesson)?;
+            print_warnings(&lesson, session);
+
+            info!("Added lesson with ID {} from the command line!", id);
+            writeln!(out, "{}", id)?;
+        }
+        Command::Update(id, lesson_args) => {
+            let mut lesson = match tipp10::get_lessons(conn)?
+                .into_iter()
+                .find(|lesson| lesson.id == *id)
+            {
+                Some(lesson) => lesson,
+                None => return Err(CliError::SQLite(SQLiteError::LessonNotFound(*id))),
+            };
+
+            // Keep every field that was not given on the command line
+            if let Some(number) = lesson_args.lesson {
+                let catalogue = LessonCatalogue::load(conn, session.language);
+                lesson.set_catalogue_lesson(get_catalogue_lesson(&catalogue, number)?);
+            };
+            lesson.strokes = lesson_args.strokes.unwrap_or(lesson.strokes);
+            lesson.errors = lesson_args.errors.unwrap_or(lesson.errors);
+            lesson.tokens = lesson_args.tokens.unwrap_or(lesson.tokens);
+            lesson.duration = lesson_args.duration.unwrap_or(lesson.duration);
+            lesson.timestamp = lesson_args.timestamp.unwrap_or(lesson.timestamp);
+            lesson.lesson_type = lesson_args.lesson_type.unwrap_or(lesson.lesson_type);
+
+            tipp10::update_lesson(conn, session, &lesson)?;
+            print_warnings(&lesson, session);
+
+            info!("Updated lesson with ID {} from the command line!", id);
+        }
+        Command::Delete(id) => {
+            tipp10::delete_lesson(conn, session, *id)?;
+
+            info!("Deleted lesson with ID {} from the command line!", id);
+        }
+        Command::Export(file) => {
+            let count = match file {
+                Some(file) => csv::export_lessons(conn, &mut File::create(file)?)?,
+                None => csv::export_lessons(conn, out)?,
+            };
+
+            info!("Exported {} lessons from the command line!", count);
+        }
+        Command::Import(import_args) => {
+            let input = if import_args.file == "-" {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                input
+            } else {
+                fs::read_to_string(&import_args.file)?
+            };
+
+            let report = csv::import_lessons(
+                conn,
+                session,
+                &input,
+                &import_args.mapping,
+                import_args.all_or_nothing,
+            )?;
+            writeln!(out, "Imported {} lessons.", report.imported)?;
+            for warning in &report.warnings {
+                eprintln!("Warning: {}", warning);
+            }
+
+            if !report.errors.is_empty() {
+                return Err(CliError::PartialImport(report.errors));
+            };
+        }
+        Command::Compact => {
+            tipp10::reset_ids(conn, session)?;
+
+            info!("Compacted lesson IDs from the command line!");
+        }
+        Command::Backups => {
+            for backup in backup::list_backups(conn)? {
+                let lessons = match backup.lessons {
+                    Some(lessons) => lessons.to_string(),
+                    None => "unreadable".to_string(),
+                };
+                writeln!(
+                    out,
+                    "{}\t{} lessons\t{}",
+                    backup.timestamp,
+                    lessons,
+                    backup.get_file_name()
+                )?;
+            }
+        }
+        Command::Restore(name) => {
+            // A bare file name refers to a backup in the backup directory
+            let path = match backup::list_backups(conn)?
+                .into_iter()
+                .find(|backup| backup.get_file_name() == *name)
+            {
+                Some(backup) => backup.path,
+                None => Path::new(name).to_path_buf(),
+            };
+
+            backup::restore_backup(conn, session, &path)?;
+
+            info!("Restored backup {} from the command line!", path.display());
+            writeln!(out, "Restored {}", path.display())?;
+        }
+        Command::Help => writeln!(out, "{}", USAGE)?,
+    };
+
+    Ok(())
+}
+
+/// Get a lesson of the catalogue by its number.
+fn get_catalogue_lesson(
+    catalogue: &LessonCatalogue,
+    number: usize,
+) -> Result<&CatalogueLesson, CliError> {
+    catalogue.get(number).ok_or_else(|| {
+        CliError::Usage(format!(
+            "Unknown lesson: {} (the database has lessons 1-{})",
+            number,
+            catalogue.len()
+        ))
+    })
+}
+
+/// Take the global options out of the arguments. Returns the options and the other arguments.
+fn parse_global_options(args: &[String]) -> Result<(GlobalOptions, Vec<String>), CliError> {
+    let mut options = GlobalOptions::default();
+    let mut rest = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let option = arg.as_str();
+        let mut value = || match args.next() {
+            Some(value) => Ok(value.as_str()),
+            None => Err(CliError::Usage(format!("Missing value for {}", option))),
+        };
+        let invalid =
+            |value: &str| CliError::Usage(format!("Invalid value for {}: {}", option, value));
+
+        let session = &mut options.session;
+        let rules = &mut session.validation_rules;
+        match option {
+            "-d" | "--debug" => options.debug = true,
+            "--stable-ids" => session.id_mode = IdMode::Stable,
+            "--backups" => {
+                let value = value()?;
+                session.backup_count = value.parse().map_err(|_| invalid(value))?;
+            }
+            "--language" => {
+                let value = value()?;
+                session.language =
+                    Some(Language::try_from_code(value).ok_or_else(|| invalid(value))?);
+            }
+            "--allow-future" => rules.allow_future = true,
+            "--max-cpm" => {
+                let value = value()?;
+                rules.max_cpm = parse_limit(value).ok_or_else(|| invalid(value))?;
+            }
+            "--max-duration" => {
+                let value = value()?;
+                rules.max_duration =
+                    tipp10::duration::parse_duration(value).ok_or_else(|| invalid(value))?;
+            }
+            "--max-error-rate" => {
+                let value = value()?;
+                rules.max_error_rate = parse_limit(value).ok_or_else(|| invalid(value))?;
+            }
+            _ => rest.push(arg.clone()),
+        };
+    }
+
+    Ok((options, rest))
+}
+
+/// Parse the limit of a validation rule. It has to be a positive number.
+fn parse_limit(value: &str) -> Option<f64> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.0)
+}
+
+/// Parse a lesson ID argument.
+fn parse_id(arg: Option<&String>) -> Result<usize, CliError> {
+    match arg {
+        Some(arg) => arg
+            .parse::<usize>()
+            .map_err(|_| CliError::Usage(format!("Invalid lesson ID: {}", arg))),
+        None => Err(CliError::Usage("Missing lesson ID!".to_string())),
+    }
+}
+
+/// Parse the optional `--format` option.
+fn parse_format<'a>(args: &mut impl Iterator<Item = &'a String>) -> Result<OutputFormat, CliError> {
+    match args.next().map(|arg| arg.as_str()) {
+        Some("--format") => match args.next() {
+            Some(name) => OutputFormat::from_name(name)
+                .ok_or_else(|| CliError::Usage(format!("Unknown format: {}", name))),
+            None => Err(CliError::Usage("Missing value for --format".to_string())),
+        },
+        Some(option) => Err(CliError::Usage(format!("Unknown option: {}", option))),
+        None => Ok(OutputFormat::default()),
+    }
+}
+
+/// Parse the `--option value` pairs describing a lesson.
+fn parse_lesson_args<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+) -> Result<LessonArgs, CliError> {
+    let mut lesson_args = LessonArgs::default();
+
+    while let Some(option) = args.next() {
+        let value = match args.next() {
+            Some(value) => value.as_str(),
+            None => return Err(CliError::Usage(format!("Missing value for {}", option))),
+        };
+        let invalid = || CliError::Usage(format!("Invalid value for {}: {}", option, value));
+
+        match option.as_str() {
+            "--lesson" => {
+                // The lesson is checked against the catalogue of the database when it is opened
+                let lesson = value.parse::<usize>().map_err(|_| invalid())?;
+                if lesson == 0 {
+                    return Err(invalid());
+                };
+                lesson_args.lesson = Some(lesson);
+            }
+            "--strokes" => lesson_args.strokes = Some(value.parse().map_err(|_| invalid())?),
+            "--errors" => lesson_args.errors = Some(value.parse().map_err(|_| invalid())?),
+            "--tokens" => lesson_args.tokens = Some(value.parse().map_err(|_| invalid())?),
+            "--duration" => {
+                lesson_args.duration =
+                    Some(tipp10::duration::parse_duration(value).ok_or_else(invalid)?)
+            }
+            "--timestamp" => {
+                lesson_args.timestamp = Some(
+                    tipp10::date_input::get_timestamp_from_input(value).map_err(|_| invalid())?,
+                )
+            }
+            "--type" => {
+                lesson_args.lesson_type =
+                    Some(LessonType::try_from_name(value).ok_or_else(invalid)?)
+            }
+            option => return Err(CliError::Usage(format!("Unknown option: {}", option))),
+        };
+    }
+
+    Ok(lesson_args)
+}
+
+/// Parse the file and options of the import command.
+fn parse_import_args<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+) -> Result<ImportArgs, CliError> {
+    let mut import_args = ImportArgs {
+        file: match args.next() {
+            Some(file) => file.clone(),
+            None => return Err(CliError::Usage("Missing CSV file!".to_string())),
+        },
+        ..Default::default()
+    };
+
+    while let Some(option) = args.next() {
+        match option.as_str() {
+            "--all-or-nothing" => import_args.all_or_nothing = true,
+            "--map" => {
+                let value = match args.next() {
+                    Some(value) => value,
+                    None => return Err(CliError::Usage("Missing value for --map".to_string())),
+                };
+
+                let mapping = value.rsplit_once('=').and_then(|(column, field)| {
+                    CsvField::from_name(field).map(|field| (column.to_string(), field))
+                });
+                match mapping {
+                    Some(mapping) => import_args.mapping.push(mapping),
+                    None => {
+                        return Err(CliError::Usage(format!(
+                            "Invalid value for --map: {}",
+                            value
+                        )))
+                    }
+                };
+            }
+            option => return Err(CliError::Usage(format!("Unknown option: {}", option))),
+        };
+    }
+
+    Ok(import_args)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::{new_lesson, setup_test_db};
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_no_command() {
+        assert_eq!(Cli::parse(&[]).unwrap(), (GlobalOptions::default(), None));
+    }
+
+    #[test]
+    fn test_parse_add() {
+        let cli = Cli::parse(&args(&["add", "/tipp10", "--lesson", "3", "--errors", "4"]))
+            .unwrap()
+            .1
+            .unwrap();
+        assert_eq!(cli.path, "/tipp10");
+        assert_eq!(
+            cli.command,
+            Command::Add(LessonArgs {
+                lesson: Some(3),
+                errors: Some(4),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_list_format() {
+        let cli = Cli::parse(&args(&["list", "/tipp10", "--format", "json"]))
+            .unwrap()
+            .1
+            .unwrap();
+        assert_eq!(cli.command, Command::List(OutputFormat::Json));
+        assert!(Cli::parse(&args(&["list", "/tipp10", "--format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn test_execute_list_json() {
+        let mut conn = setup_test_db();
+        tipp10::append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        tipp10::append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(2, 200, 20, 120, 20230101130000),
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        execute(
+            &mut conn,
+            &Command::List(OutputFormat::Json),
+            &mut Session::default(),
+            &mut out,
+        )
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("[\n  {\"id\":0,"));
+        assert!(out.contains("},\n  {\"id\":1,"));
+        assert!(out.ends_with("}\n]\n"));
+    }
+
+    #[test]
+    fn test_parse_import() {
+        let cli = Cli::parse(&args(&[
+            "import",
+            "/tipp10",
+            "lessons.csv",
+            "--map",
+            "Date=timestamp",
+            "--all-or-nothing",
+        ]))
+        .unwrap()
+        .1
+        .unwrap();
+        assert_eq!(
+            cli.command,
+            Command::Import(ImportArgs {
+                file: "lessons.csv".to_string(),
+                mapping: vec![("Date".to_string(), CsvField::Timestamp)],
+                all_or_nothing: true,
+            })
+        );
+        assert!(Cli::parse(&args(&["import", "/tipp10", "a.csv", "--map", "Date=when"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_global_options() {
+        let (options, cli) = Cli::parse(&args(&[
+            "--backups",
+            "3",
+            "list",
+            "/tipp10",
+            "-d",
+            "--language",
+            "de",
+            "--max-duration",
+            "1h",
+            "--allow-future",
+        ]))
+        .unwrap();
+        assert_eq!(cli.unwrap().command, Command::List(OutputFormat::Text));
+        assert!(options.debug);
+        assert_eq!(options.session.backup_count, 3);
+        assert_eq!(options.session.language, Some(Language::German));
+        assert_eq!(options.session.validation_rules.max_duration, 3600);
+        assert!(options.session.validation_rules.allow_future);
+
+        // The options also apply to the user interface
+        let (options, cli) = Cli::parse(&args(&["--stable-ids"])).unwrap();
+        assert_eq!(options.session.id_mode, IdMode::Stable);
+        assert!(cli.is_none());
+
+        for wrong in [
+            vec!["--backups"],
+            vec!["--backups", "-1"],
+            vec!["--language", "xx"],
+            vec!["--max-cpm", "fast"],
+            vec!["--max-error-rate", "-5"],
+        ] {
+            assert!(matches!(Cli::parse(&args(&wrong)), Err(CliError::Usage(_))));
+        }
+    }
+
+    #[test]
+    fn test_parse_wrong_usage() {
+        assert!(Cli::parse(&args(&["list"])).is_err());
+        assert!(Cli::parse(&args(&["delete", "/tipp10"])).is_err());
+        assert!(Cli::parse(&args(&["delete", "/tipp10", "x"])).is_err());
+        assert!(Cli::parse(&args(&["add", "/tipp10", "--lesson", "0"])).is_err());
+        assert!(Cli::parse(&args(&["add", "/tipp10", "--strokes"])).is_err());
+        assert!(Cli::parse(&args(&["frobnicate", "/tipp10"])).is_err());
+    }
+
+    #[test]
+    fn test_execute_add_update_delete() {
+        let mut conn = setup_test_db();
+        let mut out = Vec::new();
+
+        execute(
+            &mut conn,
+            &Command::Add(LessonArgs {
+                strokes: Some(300),
+                ..Default::default()
+            }),
+            &mut Session::default(),
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "0\n");
+
+        execute(
+            &mut conn,
+            &Command::Update(
+                0,
+                LessonArgs {
+                    errors: Some(7),
+                    ..Default::default()
+                },
+            ),
+            &mut Session::default(),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        let lessons = tipp10::get_lessons(&conn).unwrap();
+        assert_eq!(lessons[0].strokes, 300);
+        assert_eq!(lessons[0].errors, 7);
+        assert_eq!(lessons[0].tokens, 300);
+
+        // The lesson number must be in the catalogue of the database
+        let result = execute(
+            &mut conn,
+            &Command::Add(LessonArgs {
+                lesson: Some(21),
+                ..Default::default()
+            }),
+            &mut Session::default(),
+            &mut Vec::new(),
+        );
+        assert!(matches!(result, Err(CliError::Usage(_))));
+
+        let result = execute(
+            &mut conn,
+            &Command::Delete(1),
+            &mut Session::default(),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+        execute(
+            &mut conn,
+            &Command::Delete(0),
+            &mut Session::default(),
+            &mut Vec::new(),
+        )
+        .unwrap();
+        assert!(tipp10::get_lessons(&conn).unwrap().is_empty());
+    }
+}
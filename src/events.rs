@@ -28,11 +28,10 @@ impl Tipp10W {
 
         let result: EventResult = match &mut self.app_state.state {
             State::Setup => {
-                match event {
-                    Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                        if key_event.code == KeyCode::Esc { return Ok(EventResult::Exit) }
+                if let Event::Key(key_event) = event {
+                    if key_event.kind == KeyEventKind::Press && key_event.code == KeyCode::Esc {
+                        return Ok(EventResult::Exit);
                     }
-                    _ => (),
                 };
 
                 let event_result = self.app_state.text_box.handle_events(&event)?;
@@ -62,7 +61,7 @@ impl Tipp10W {
                         if let Some(conn) = &self.conn {
                             self.app_state
                                 .lessons_widget
-                                .update_lessons_and_move_ptr_to_last(conn);
+                                .update_lessons_and_move_ptr_to_last(conn, &self.app_state.session);
 
                             self.app_state.text_box.reset();
                         };
@@ -79,8 +78,28 @@ impl Tipp10W {
                         event,
                         sub_state,
                         conn,
+                        &mut self.app_state.session,
                         &mut self.app_state.text_box,
                     ) {
+                        EventResult::SetState(State::Backups) => {
+                            // Load the backups before showing them
+                            let event_result = self.app_state.backups_widget.update_backups(conn);
+                            self.app_state.state = State::Backups;
+                            self.show_result_error(event_result)
+                        }
+                        EventResult::SetState(State::Statistics) => {
+                            // Load the lessons before showing the statistics
+                            let event_result =
+                                self.app_state.statistics_widget.update_lessons(conn);
+                            self.app_state.state = State::Statistics;
+                            self.show_result_error(event_result)
+                        }
+                        EventResult::SetState(State::Calendar) => {
+                            // Load the lessons before showing the calendar
+                            let event_result = self.app_state.calendar_widget.update_lessons(conn);
+                            self.app_state.state = State::Calendar;
+                            self.show_result_error(event_result)
+                        }
                         EventResult::SetState(state) => {
                             // Change the application state
                             self.app_state.state = state;
@@ -89,23 +108,45 @@ impl Tipp10W {
                         EventResult::SetSubState(sub_state) => {
                             // Change the substate of the Menu state
                             self.app_state.state = State::Menu(sub_state);
-                            EventResult::None(ResultError::None)
-                        }
-                        EventResult::None(result_error) => match result_error {
-                            ResultError::None => {
-                                // Clear any existing error message
-                                self.app_state.error = String::new();
-                                EventResult::None(ResultError::None)
-                            }
-                            result_error => {
-                                // Set the error message and return the result error
-                                let e = result_error.to_string();
-                                self.app_state.error = e;
 
-                                EventResult::None(result_error)
+                            // Show the message about the last change, like how many lessons a bulk
+                            // action changed or the warnings of a saved lesson
+                            match self.app_state.lessons_widget.take_notice() {
+                                Some(notice) => self.show_result_error(EventResult::None(notice)),
+                                None => EventResult::None(ResultError::None),
                             }
-                        },
-                        event_result => event_result, // Return the original event result for other cases
+                        }
+                        event_result => self.show_result_error(event_result),
+                    }
+                } else {
+                    // If the connection is None, return to the Setup state
+                    self.conn = None;
+                    EventResult::SetState(State::Setup)
+                }
+            }
+            State::Backups => {
+                if let Some(conn) = &mut self.conn {
+                    // Handle events for the BackupsWidget
+                    match self.app_state.backups_widget.handle_events(
+                        event,
+                        conn,
+                        &mut self.app_state.session,
+                    ) {
+                        EventResult::SetState(state) => {
+                            // Reload the lessons because a backup may have been restored
+                            self.app_state
+                                .lessons_widget
+                                .update_lessons(conn, &self.app_state.session);
+                            if self.app_state.lessons_widget.ptr
+                                >= self.app_state.lessons_widget.lessons.len()
+                            {
+                                self.app_state.lessons_widget.move_ptr_to_last();
+                            };
+
+                            self.app_state.state = state;
+                            self.show_result_error(EventResult::None(ResultError::None))
+                        }
+                        event_result => self.show_result_error(event_result),
                     }
                 } else {
                     // If the connection is None, return to the Setup state
@@ -113,8 +154,45 @@ impl Tipp10W {
                     EventResult::SetState(State::Setup)
                 }
             }
+            State::Statistics => {
+                // Handle events for the StatisticsWidget
+                match self.app_state.statistics_widget.handle_events(&event) {
+                    EventResult::SetState(state) => {
+                        self.app_state.state = state;
+                        self.show_result_error(EventResult::None(ResultError::None))
+                    }
+                    event_result => self.show_result_error(event_result),
+                }
+            }
+            State::Calendar => {
+                // Handle events for the CalendarWidget
+                match self.app_state.calendar_widget.handle_events(&event) {
+                    EventResult::SetState(state) => {
+                        self.app_state.state = state;
+                        self.show_result_error(EventResult::None(ResultError::None))
+                    }
+                    event_result => self.show_result_error(event_result),
+                }
+            }
         };
 
         Ok(result)
     }
+
+    /// Show the error of an event result in the status bar or clear the status bar if there is none.
+    fn show_result_error(&mut self, event_result: EventResult) -> EventResult {
+        match event_result {
+            EventResult::None(ResultError::None) => {
+                // Clear any existing error message
+                self.app_state.error = String::new();
+                EventResult::None(ResultError::None)
+            }
+            EventResult::None(result_error) => {
+                // Set the error message and return the result error
+                self.app_state.error = result_error.to_string();
+                EventResult::None(result_error)
+            }
+            event_result => event_result, // Return the original event result for other cases
+        }
+    }
 }
@@ -2,9 +2,11 @@ use std::{
     env,
     fs::File,
     io::{self, Write},
+    process::ExitCode,
 };
 
 // Module declarations
+mod cli;
 pub mod events;
 pub mod state;
 mod tipp10;
@@ -12,6 +14,7 @@ mod tipp10w;
 pub mod ui;
 pub mod widgets;
 
+use cli::Cli;
 use env_logger::Builder;
 use tipp10w::Tipp10W;
 
@@ -44,20 +47,24 @@ fn init_logger() {
     };
 }
 
-fn main() -> io::Result<()> {
-    let args = env::args().collect::<Vec<String>>();
-    if !args.is_empty() {
-        for a in args.iter() {
-            match a.as_str() {
-                "-d" | "--debug" => {
-                    init_logger();
-                }
-                _ => {}
-            }
+fn main() -> io::Result<ExitCode> {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let (options, cli) = match Cli::parse(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, cli::USAGE);
+            return Ok(e.exit_code());
         }
-    } else {
-        env_logger::init();
-    }
+    };
+    if options.debug {
+        init_logger();
+    };
+    let session = options.session;
+
+    // Run a non-interactive command if one was given
+    if let Some(cli) = cli {
+        return Ok(cli.run(session));
+    };
 
     enable_bracketed_paste();
 
@@ -65,11 +72,13 @@ fn main() -> io::Result<()> {
         ratatui::init();
 
     // Create a new instance of the application and run it.
-    let app_result = Tipp10W::new().run(&mut terminal);
+    let mut app = Tipp10W::new();
+    app.app_state.session = session;
+    let app_result = app.run(&mut terminal);
 
     ratatui::restore();
 
     disable_bracketed_paste();
 
-    app_result
+    app_result.map(|_| ExitCode::SUCCESS)
 }
@@ -1,14 +1,19 @@
-use crate::widgets::{LessonsWidget, TextBox}; // Importing necessary widgets
+use crate::tipp10::Session;
+use crate::widgets::{BackupsWidget, CalendarWidget, LessonsWidget, StatisticsWidget, TextBox}; // Importing necessary widgets
 
 // Type alias for ID to improve code readability
 pub type ID = usize;
 
 // Main application state struct
 pub struct AppState {
-    pub state: State,                  // Current state of the application
-    pub text_box: TextBox,             // TextBox widget for user input
-    pub lessons_widget: LessonsWidget, // LessonsWidget to display lessons
-    pub error: String,                 // String to store error messages
+    pub state: State,                        // Current state of the application
+    pub text_box: TextBox,                   // TextBox widget for user input
+    pub lessons_widget: LessonsWidget,       // LessonsWidget to display lessons
+    pub backups_widget: BackupsWidget,       // BackupsWidget to display backups
+    pub statistics_widget: StatisticsWidget, // StatisticsWidget to display the progress
+    pub calendar_widget: CalendarWidget,     // CalendarWidget to display the practice per day
+    pub error: String,                       // String to store error messages
+    pub session: Session,                    // Settings and state of the writes to the database
 }
 
 impl Default for AppState {
@@ -21,10 +26,14 @@ impl AppState {
     /// Creates a new instance of AppState with default values
     pub fn new() -> Self {
         Self {
-            state: State::Setup,                  // Initial state is Setup
-            text_box: TextBox::new(None),         // Initialize TextBox with no max length
-            lessons_widget: LessonsWidget::new(), // Initialize LessonsWidget
-            error: String::new(),                 // Initialize error message as an empty string
+            state: State::Setup,                        // Initial state is Setup
+            text_box: TextBox::new(None),               // Initialize TextBox with no max length
+            lessons_widget: LessonsWidget::new(),       // Initialize LessonsWidget
+            backups_widget: BackupsWidget::new(),       // Initialize BackupsWidget
+            statistics_widget: StatisticsWidget::new(), // Initialize StatisticsWidget
+            calendar_widget: CalendarWidget::new(),     // Initialize CalendarWidget
+            error: String::new(), // Initialize error message as an empty string
+            session: Session::default(), // Initialize Session with default settings
         }
     }
 }
@@ -34,11 +43,17 @@ impl AppState {
 pub enum State {
     Setup,          // Initial setup state
     Menu(SubState), // Menu state with a substate
+    Backups,        // Backups state listing the backups of the database
+    Statistics,     // Statistics state showing the progress in charts
+    Calendar,       // Calendar state showing the practice time per day
 }
 
 // Enum representing the substates of the Menu state
 #[derive(Debug, PartialEq)]
 pub enum SubState {
-    Edit(ID), // Edit substate with an ID
-    None,     // No substate
+    Edit(ID),   // Edit substate with an ID
+    Delete(ID), // Delete substate asking to confirm the deletion of the lesson with an ID
+    Search,     // Search substate typing the filter of the lessons
+    Bulk,       // Bulk substate changing or deleting the marked lessons
+    None,       // No substate
 }
@@ -1,19 +1,37 @@
+pub mod backup;
+pub mod bulk;
+pub mod calendar;
+mod catalogue;
+pub mod csv;
+pub mod date_input;
+pub mod duration;
+pub mod filter;
+mod history;
+mod language;
 mod lesson;
-mod lesson_selection;
+mod lesson_type;
+mod session;
+pub mod sort;
+pub mod statistics;
+pub mod validation;
 
-use std::fmt::Display;
+use std::{fmt::Display, io};
 
+pub use catalogue::{CatalogueLesson, LessonCatalogue};
 use chrono::{offset::LocalResult, DateTime, Local, NaiveDateTime, TimeZone};
+pub use history::{History, Operation};
+pub use language::Language;
 pub use lesson::Lesson;
-pub use lesson_selection::LessonSelection;
+pub use lesson_type::LessonType;
 use log::{error, info, trace, warn};
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+pub use session::Session;
 
 /// Get all saved lessons.
 pub fn get_lessons(conn: &Connection) -> Result<Vec<Lesson>, rusqlite::Error> {
     let mut stmt = match conn
             .prepare(
-                "SELECT user_lesson_id, user_lesson_name, user_lesson_timestamp, user_lesson_timelen, user_lesson_strokesnum, user_lesson_errornum FROM user_lesson_list",
+                "SELECT user_lesson_id, user_lesson_name, user_lesson_timestamp, user_lesson_timelen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_type, user_lesson_lesson, user_lesson_tokenlen FROM user_lesson_list",
             ) {
                 Ok(stmt) => stmt,
                 Err(e) => {
@@ -23,18 +41,8 @@ pub fn get_lessons(conn: &Connection) -> Result<Vec<Lesson>, rusqlite::Error> {
             };
     trace!("Getting all lessons from database!");
 
-    let lessons_iter = match stmt.query_map(params![], |row| {
-        Ok(Lesson::new(
-            row.get(0)?,
-            LessonSelection::from_lesson_name(&row.get::<_, String>(1)?),
-            row.get::<_, String>(2)?
-                .parse::<u64>()
-                .expect("Should not happen!"),
-            row.get(3)?,
-            row.get(4)?,
-            row.get(5)?,
-        ))
-    }) {
+    let catalogue = LessonCatalogue::load(conn, None);
+    let lessons_iter = match stmt.query_map(params![], |row| read_lesson(row, &catalogue)) {
         Ok(stmt) => stmt,
         Err(e) => {
             error!("Could not get all lessons! Error: {}", e);
@@ -64,83 +72,393 @@ pub fn get_lessons(conn: &Connection) -> Result<Vec<Lesson>, rusqlite::Error> {
     Ok(lessons)
 }
 
-/// Append a lesson to the table.
+/// Read a lesson from a row of id, name, timestamp, timelen, strokesnum, errornum, type, lesson and
+/// tokenlen. The lesson is looked up in the catalogue.
+fn read_lesson(row: &Row, catalogue: &LessonCatalogue) -> Result<Lesson, rusqlite::Error> {
+    let lesson = Lesson::new(
+        row.get(0)?,
+        catalogue.get_default(),
+        row.get::<_, String>(2)?
+            .parse::<u64>()
+            .expect("Should not happen!"),
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+    )
+    .with_stored_lesson(
+        &row.get::<_, String>(7)?,
+        &row.get::<_, String>(1)?,
+        catalogue,
+    )
+    .with_lesson_type(LessonType::from_type(row.get(6)?));
+
+    // Rows without a token length keep the number of strokes
+    Ok(match row.get::<_, Option<usize>>(8)? {
+        Some(tokens) => lesson.with_tokens(tokens),
+        None => lesson,
+    })
+}
+
+/// How the lesson IDs are kept when lessons are appended or deleted.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum IdMode {
+    /// Renumber the lessons from 0 whenever a change leaves a gap in the IDs.
+    #[default]
+    Compact,
+    /// Keep the IDs of existing lessons. Gaps are only closed by an explicit [`reset_ids`].
+    Stable,
+}
+
+/// Append a lesson to the table. The ID of the lesson is ignored and the new ID is returned.
 pub fn append_lesson(
     conn: &Connection,
-    lesson_id: u8,
-    strokes: usize,
-    errors: usize,
-    timelen: usize,
-    timestamp: u64,
+    session: &mut Session,
+    lesson: &Lesson,
 ) -> Result<usize, SQLiteError> {
-    let lesson = LessonSelection::from_lesson_id(lesson_id);
+    validation::check_lesson(lesson, &session.validation_rules)?;
+    session.before_write(conn)?;
+    compact_before_write(conn, session)?;
 
-    match conn.execute(
-        "INSERT INTO user_lesson_list (user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-        params![lesson.get_user_lesson(), timelen, strokes, strokes, errors, timestamp.to_string(), 0, lesson.get_lesson_name()],
-    ) {
+    let lesson = atomic(conn, || {
+        let mut lesson = lesson.clone();
+
+        // The new lesson always gets the next ID so no existing lesson has to be renumbered
+        lesson.id = conn
+            .query_row(
+                "SELECT IFNULL(MAX(user_lesson_id) + 1, 0) FROM user_lesson_list",
+                params![],
+                |row| row.get(0),
+            )
+            .map_err(SQLiteError::RusqliteError)?;
+        insert_lesson(conn, &lesson)?;
+
+        info!("Insertion completed!");
+        Ok(lesson)
+    })?;
+
+    let id = lesson.id;
+    session.history.record(Operation::Append(lesson));
+    Ok(id)
+}
+
+/// Update a lesson in the table. The row is selected by `lesson.id`.
+pub fn update_lesson(
+    conn: &Connection,
+    session: &mut Session,
+    lesson: &Lesson,
+) -> Result<(), SQLiteError> {
+    validation::check_lesson(lesson, &session.validation_rules)?;
+    session.before_write(conn)?;
+
+    let before = atomic(conn, || {
+        let before = get_lesson(conn, lesson.id)?;
+        write_lesson(conn, lesson)?;
+
+        info!("Update completed!");
+        Ok(before)
+    })?;
+
+    session.history.record(Operation::Update {
+        before,
+        after: lesson.clone(),
+    });
+    Ok(())
+}
+
+/// Delete a lesson to the table.
+pub fn delete_lesson(
+    conn: &Connection,
+    session: &mut Session,
+    id: usize,
+) -> Result<(), SQLiteError> {
+    session.before_write(conn)?;
+    let id = get_compacted_id(&compact_before_write(conn, session)?, id);
+
+    let operations = atomic(conn, || remove_lessons(conn, session.id_mode, &[id]))?;
+    info!("Lesson deletetion completed! Lesson ID: {}", id);
+
+    for operation in operations {
+        session.history.record(operation);
+    }
+    Ok(())
+}
+
+/// Revert the last append, update or delete of the session.
+///
+/// Returns the ID of the changed lesson or None if there is nothing to undo.
+pub fn undo(conn: &Connection, session: &mut Session) -> Result<Option<usize>, SQLiteError> {
+    if session.history.has_undo() {
+        session.before_write(conn)?;
+        compact_before_write(conn, session)?;
+    };
+
+    let operation = match session.history.pop_undo() {
+        Some(operation) => operation,
+        None => return Ok(None),
+    };
+
+    match apply_operation(conn, session, &operation.inverse()) {
         Ok(_) => {
-            trace!("Lesson appended to database!");
-        },
+            info!("Undo completed!");
+            let id = operation.get_id();
+            session.history.push_redo(operation);
+            Ok(Some(id))
+        }
         Err(e) => {
-            error!("Could not append lesson to database!");
-            return Err(SQLiteError::RusqliteError(e));
+            session.history.push_undo(operation);
+            Err(e)
         }
+    }
+}
+
+/// Apply the last undone operation again.
+///
+/// Returns the ID of the changed lesson or None if there is nothing to redo.
+pub fn redo(conn: &Connection, session: &mut Session) -> Result<Option<usize>, SQLiteError> {
+    if session.history.has_redo() {
+        session.before_write(conn)?;
+        compact_before_write(conn, session)?;
     };
 
-    reset_ids(conn)?;
+    let operation = match session.history.pop_redo() {
+        Some(operation) => operation,
+        None => return Ok(None),
+    };
 
-    info!("Insertion completed!");
-    get_last_lesson_id(conn)
+    match apply_operation(conn, session, &operation) {
+        Ok(_) => {
+            info!("Redo completed!");
+            let id = operation.get_id();
+            session.history.push_undo(operation);
+            Ok(Some(id))
+        }
+        Err(e) => {
+            session.history.push_redo(operation);
+            Err(e)
+        }
+    }
 }
 
-/// Update a lesson in the table.
-pub fn update_lesson(
+/// Apply an operation without recording it.
+///
+/// Undoing the operations in reverse order restores the IDs as well, so the recorded IDs stay valid.
+fn apply_operation(
     conn: &Connection,
-    id: usize,
-    lesson_id: u8,
-    strokes: usize,
-    errors: usize,
-    timelen: usize,
-    timestamp: u64,
+    session: &mut Session,
+    operation: &Operation,
+) -> Result<(), SQLiteError> {
+    session.before_write(conn)?;
+
+    let id_mode = session.id_mode;
+    atomic(conn, || write_operation(conn, id_mode, operation))
+}
+
+/// Write an operation to the table. The operations of a batch are written in their order.
+fn write_operation(
+    conn: &Connection,
+    id_mode: IdMode,
+    operation: &Operation,
 ) -> Result<(), SQLiteError> {
-    let lesson = LessonSelection::from_lesson_id(lesson_id);
+    match operation {
+        Operation::Append(lesson) => insert_lesson(conn, lesson)?,
+        Operation::Update { after, .. } => write_lesson(conn, after)?,
+        Operation::Delete(lesson) => {
+            remove_lesson(conn, lesson.id)?;
+
+            if id_mode == IdMode::Compact && has_id_gaps(conn)? {
+                compact_ids(conn)?;
+            };
+        }
+        Operation::Batch(operations) => {
+            for operation in operations {
+                write_operation(conn, id_mode, operation)?;
+            }
+        }
+    };
+
+    Ok(())
+}
+
+/// Get a lesson by ID.
+pub fn get_lesson(conn: &Connection, id: usize) -> Result<Lesson, SQLiteError> {
+    let catalogue = LessonCatalogue::load(conn, None);
+    match conn
+        .query_row(
+            "SELECT user_lesson_id, user_lesson_name, user_lesson_timestamp, user_lesson_timelen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_type, user_lesson_lesson, user_lesson_tokenlen FROM user_lesson_list WHERE user_lesson_id = ?1",
+            params![id],
+            |row| read_lesson(row, &catalogue),
+        )
+        .optional()
+    {
+        Ok(Some(lesson)) => Ok(lesson),
+        Ok(None) => {
+            error!("Could not find lesson! ID: {}", id);
+            Err(SQLiteError::LessonNotFound(id))
+        }
+        Err(e) => {
+            error!("Could not get lesson from database! Error: {}", e);
+            Err(SQLiteError::RusqliteError(e))
+        }
+    }
+}
+
+/// Insert a lesson with its ID. The lessons from this ID on are moved up by one if it is taken.
+fn insert_lesson(conn: &Connection, lesson: &Lesson) -> Result<(), SQLiteError> {
+    // Negate the IDs first, so no ID is taken twice while moving them
+    conn.execute(
+        "UPDATE user_lesson_list SET user_lesson_id = -user_lesson_id - 1 WHERE user_lesson_id >= ?1 AND EXISTS (SELECT 1 FROM user_lesson_list WHERE user_lesson_id = ?1)",
+        params![lesson.id],
+    )
+    .map_err(SQLiteError::RusqliteError)?;
+    conn.execute(
+        "UPDATE user_lesson_list SET user_lesson_id = -user_lesson_id WHERE user_lesson_id < 0",
+        params![],
+    )
+    .map_err(SQLiteError::RusqliteError)?;
+
+    match conn.execute(
+        "INSERT INTO user_lesson_list (user_lesson_id, user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![lesson.id, lesson.user_lesson, lesson.duration, lesson.tokens, lesson.strokes, lesson.errors, lesson.timestamp.to_string(), lesson.lesson_type.get_type(), lesson.name],
+    ) {
+        Ok(_) => {
+            trace!("Lesson inserted into database!");
+            Ok(())
+        }
+        Err(e) => {
+            error!("Could not insert lesson into database!");
+            Err(SQLiteError::RusqliteError(e))
+        }
+    }
+}
 
+/// Write all fields of a lesson to the row with its ID.
+fn write_lesson(conn: &Connection, lesson: &Lesson) -> Result<(), SQLiteError> {
     match conn.execute(
         "UPDATE user_lesson_list SET user_lesson_lesson = ?1, user_lesson_timelen = ?2, user_lesson_tokenlen = ?3, user_lesson_strokesnum = ?4, user_lesson_errornum = ?5, user_lesson_timestamp = ?6, user_lesson_type = ?7, user_lesson_name = ?8 WHERE user_lesson_id = ?9",
-        params![lesson.get_user_lesson(), timelen, strokes, strokes, errors, timestamp, 0, lesson.get_lesson_name(), id],
+        params![lesson.user_lesson, lesson.duration, lesson.tokens, lesson.strokes, lesson.errors, lesson.timestamp, lesson.lesson_type.get_type(), lesson.name, lesson.id],
     ) {
-        Ok(_) => {trace!("Lesson updated in database!");},
+        Ok(0) => {
+            error!("Could not find lesson to update! ID: {}", lesson.id);
+            Err(SQLiteError::LessonNotFound(lesson.id))
+        }
+        Ok(_) => {
+            trace!("Lesson updated in database!");
+            Ok(())
+        }
         Err(e) => {
             error!("Could not update lesson in database!");
-            return Err(SQLiteError::RusqliteError(e));
+            Err(SQLiteError::RusqliteError(e))
         }
-    };
-
-    info!("Update completed!");
-    Ok(())
+    }
 }
 
-/// Delete a lesson to the table.
-pub fn delete_lesson(conn: &Connection, id: usize) -> Result<(), SQLiteError> {
+/// Remove the row of a lesson without closing the gap it leaves.
+fn remove_lesson(conn: &Connection, id: usize) -> Result<(), SQLiteError> {
     match conn.execute(
         "DELETE FROM user_lesson_list WHERE user_lesson_id = ?1",
         params![id],
     ) {
-        Ok(_) => (),
+        Ok(0) => {
+            error!("Could not find lesson to delete! ID: {}", id);
+            Err(SQLiteError::LessonNotFound(id))
+        }
+        Ok(_) => Ok(()),
         Err(e) => {
             error!("Could not delete lesson!");
-            return Err(SQLiteError::RusqliteError(e));
+            Err(SQLiteError::RusqliteError(e))
+        }
+    }
+}
+
+/// Delete the lessons with the IDs and renumber the rest once in compact mode. Must run inside
+/// `atomic`, so no lesson is deleted if one is missing.
+///
+/// Returns the deletes to record, from the highest ID down. Every deleted lesson has the ID it
+/// gets back when the deletes are undone from the lowest ID up.
+pub(super) fn remove_lessons(
+    conn: &Connection,
+    id_mode: IdMode,
+    ids: &[usize],
+) -> Result<Vec<Operation>, SQLiteError> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids.dedup();
+
+    let mut lessons = Vec::new();
+    for id in &ids {
+        lessons.push(get_lesson(conn, *id)?);
+        remove_lesson(conn, *id)?;
+    }
+
+    // Remember the place of every lesson in the renumbered lessons, counting the deleted lessons
+    // below it that are restored before it
+    if id_mode == IdMode::Compact && has_id_gaps(conn)? {
+        let old_ids = compact_ids(conn)?;
+        for lesson in &mut lessons {
+            let id = lesson.id;
+            lesson.id = old_ids.iter().filter(|old_id| **old_id < id).count()
+                + ids.iter().filter(|other| **other < id).count();
         }
     };
 
-    reset_ids(conn)?;
+    Ok(lessons.into_iter().map(Operation::Delete).collect())
+}
 
-    info!("Lesson deletetion completed! Lesson ID: {}", id);
-    Ok(())
+/// Run the writes of f atomically. If f fails, every change made by it is rolled back.
+///
+/// A savepoint is used instead of a transaction, so atomic operations can be nested.
+pub fn atomic<T>(
+    conn: &Connection,
+    f: impl FnOnce() -> Result<T, SQLiteError>,
+) -> Result<T, SQLiteError> {
+    conn.execute_batch("SAVEPOINT tipp10w")
+        .map_err(SQLiteError::RusqliteError)?;
+    trace!("Savepoint created!");
+
+    match f() {
+        Ok(result) => {
+            conn.execute_batch("RELEASE tipp10w")
+                .map_err(SQLiteError::RusqliteError)?;
+            trace!("Savepoint released!");
+
+            Ok(result)
+        }
+        Err(e) => {
+            warn!("Rolling back changes! Error: {}", e);
+            if let Err(rollback_error) = conn.execute_batch("ROLLBACK TO tipp10w; RELEASE tipp10w")
+            {
+                error!("Could not roll back changes! Error: {}", rollback_error);
+            };
+
+            Err(e)
+        }
+    }
+}
+
+/// Run the writes of f atomically like [`atomic`]. The operations that f records in the history of
+/// the session are recorded as one batch if f succeeds and dropped if it is rolled back, so the
+/// history never holds changes that were not saved.
+pub fn atomic_batch<T>(
+    conn: &Connection,
+    session: &mut Session,
+    f: impl FnOnce(&mut Session) -> Result<T, SQLiteError>,
+) -> Result<T, SQLiteError> {
+    // Compact the IDs first, so the writes of f never renumber the lessons of the recorded history
+    session.before_write(conn)?;
+    compact_before_write(conn, session)?;
+
+    let history = std::mem::take(&mut session.history);
+    let result = atomic(conn, || f(session));
+    let operations = std::mem::replace(&mut session.history, history).take_undo();
+
+    if result.is_ok() && !operations.is_empty() {
+        session.history.record(Operation::Batch(operations));
+    };
+    result
 }
 
 /// Get a lesson by id.
+#[cfg(test)]
 pub fn get_last_lesson_id(conn: &Connection) -> Result<usize, SQLiteError> {
     // Get max lessons.
     let mut stmt = match conn.prepare("SELECT MAX(user_lesson_id) FROM user_lesson_list") {
@@ -176,12 +494,29 @@ pub fn get_last_lesson_id(conn: &Connection) -> Result<usize, SQLiteError> {
 pub enum SQLiteError {
     RusqliteError(rusqlite::Error),
     NoLessons(String),
+    LessonNotFound(usize),
+    Backup(String),
+    Io(io::Error),
+    /// The lesson breaks the validation rules.
+    InvalidLesson(Vec<validation::Issue>),
 }
 impl Display for SQLiteError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             SQLiteError::NoLessons(e) => write!(f, "No lessons to delete from! Error: {}", e),
             SQLiteError::RusqliteError(e) => write!(f, "Rusqlite Error: {}", e),
+            SQLiteError::LessonNotFound(id) => write!(f, "No lesson with ID {} found!", id),
+            SQLiteError::Backup(e) => write!(f, "Backup Error: {}", e),
+            SQLiteError::Io(e) => write!(f, "Io Error: {}", e),
+            SQLiteError::InvalidLesson(issues) => write!(
+                f,
+                "Invalid lesson! {}",
+                issues
+                    .iter()
+                    .map(|issue| issue.message.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -202,14 +537,20 @@ pub fn get_timestamp_from_now(datetime: &DateTime<Local>) -> u64 {
         .expect("Call the IT support now! This error can not happen!")
 }
 
+/// Parse a date and time in the tipp10 format `YYYYMMDDHHmmss` without a time zone.
+pub fn get_naive_datetime_from_tipp10_str(datetime_string: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(datetime_string, "%Y%m%d%H%M%S").ok()
+}
+
 /// Get tipp10 timestamp from string.
+#[cfg(test)]
 pub fn get_datetime_tipp10_format_from_str(datetime_string: &str) -> Result<u64, ()> {
     // Ensure that the entered DateTime is a valid date
     Ok(get_timestamp_from_now(
         match &Local.from_local_datetime(
-            match &NaiveDateTime::parse_from_str(datetime_string, "%Y%m%d%H%M%S") {
-                Ok(datetime) => datetime,
-                Err(_) => return Err(()),
+            match &get_naive_datetime_from_tipp10_str(datetime_string) {
+                Some(datetime) => datetime,
+                None => return Err(()),
             },
         ) {
             LocalResult::Single(datetime) => datetime,
@@ -218,38 +559,142 @@ pub fn get_datetime_tipp10_format_from_str(datetime_string: &str) -> Result<u64,
     ))
 }
 
-/// Reset the ids of the lessons.
-pub fn reset_ids(conn: &Connection) -> Result<(), SQLiteError> {
-    conn.execute(
-        "CREATE TEMPORARY TABLE temp_table AS SELECT * FROM user_lesson_list",
-        params![],
-    )
-    .map_err(SQLiteError::RusqliteError)?;
-    trace!("Temporary table for reset ids created!");
-    conn.execute("DELETE FROM user_lesson_list", params![])
-        .map_err(SQLiteError::RusqliteError)?;
-    let mut stmt = conn.prepare("INSERT INTO user_lesson_list (user_lesson_id, user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name) SELECT row_number() OVER (ORDER BY user_lesson_id) - 1, user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name FROM temp_table").map_err(SQLiteError::RusqliteError)?;
-    stmt.execute(params![])
-        .map_err(SQLiteError::RusqliteError)?;
-    trace!("IDs resetted!");
+/// Get the local DateTime from a tipp10 timestamp.
+pub fn get_datetime_from_timestamp(timestamp: u64) -> Option<DateTime<Local>> {
+    let datetime = get_naive_datetime_from_tipp10_str(&timestamp.to_string())?;
+
+    match Local.from_local_datetime(&datetime) {
+        LocalResult::Single(datetime) => Some(datetime),
+        LocalResult::Ambiguous(datetime, _) => Some(datetime),
+        LocalResult::None => None,
+    }
+}
 
-    conn.execute("DROP TABLE temp_table", params![])
+/// Check if the lesson IDs are not numbered consecutively from 0.
+pub fn has_id_gaps(conn: &Connection) -> Result<bool, SQLiteError> {
+    let (count, max): (usize, Option<usize>) = conn
+        .query_row(
+            "SELECT COUNT(*), MAX(user_lesson_id) FROM user_lesson_list",
+            params![],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
         .map_err(SQLiteError::RusqliteError)?;
-    trace!("Temporary table dropped!");
 
-    info!("IDs reset completed!");
+    // IDs are unique, so they are consecutive from 0 if the highest ID is one less than the count
+    Ok(match max {
+        Some(max) => max + 1 != count,
+        None => false,
+    })
+}
+
+/// Reset the ids of the lessons. The history of the session follows the new IDs.
+pub fn reset_ids(conn: &Connection, session: &mut Session) -> Result<(), SQLiteError> {
+    session.before_write(conn)?;
+
+    let old_ids = compact_ids(conn)?;
+    session.history.remap(&old_ids);
+
     Ok(())
 }
+
+/// Close the gaps in the IDs of a table that was not written in Compact mode before, so the
+/// recorded operations only ever see consecutive IDs. The history of the session follows the new
+/// IDs. Returns the old IDs in the new order or nothing if the IDs did not change.
+pub(super) fn compact_before_write(
+    conn: &Connection,
+    session: &mut Session,
+) -> Result<Vec<usize>, SQLiteError> {
+    if session.id_mode != IdMode::Compact || !has_id_gaps(conn)? {
+        return Ok(Vec::new());
+    };
+
+    let old_ids = compact_ids(conn)?;
+    session.history.remap(&old_ids);
+
+    Ok(old_ids)
+}
+
+/// Get the new ID of a lesson after [`compact_before_write`]. Unknown IDs are kept.
+pub(super) fn get_compacted_id(old_ids: &[usize], id: usize) -> usize {
+    old_ids
+        .iter()
+        .position(|old_id| *old_id == id)
+        .unwrap_or(id)
+}
+
+/// Renumber the lessons from 0 in the order of their IDs. Returns the old IDs in the new order.
+fn compact_ids(conn: &Connection) -> Result<Vec<usize>, SQLiteError> {
+    let old_ids = {
+        let mut stmt = conn
+            .prepare("SELECT user_lesson_id FROM user_lesson_list ORDER BY user_lesson_id")
+            .map_err(SQLiteError::RusqliteError)?;
+        let old_ids = stmt
+            .query_map(params![], |row| row.get::<_, usize>(0))
+            .map_err(SQLiteError::RusqliteError)?
+            .collect::<Result<Vec<usize>, rusqlite::Error>>()
+            .map_err(SQLiteError::RusqliteError)?;
+        old_ids
+    };
+
+    atomic(conn, || {
+        conn.execute(
+            "CREATE TEMPORARY TABLE temp_table AS SELECT * FROM user_lesson_list",
+            params![],
+        )
+        .map_err(SQLiteError::RusqliteError)?;
+        trace!("Temporary table for reset ids created!");
+        conn.execute("DELETE FROM user_lesson_list", params![])
+            .map_err(SQLiteError::RusqliteError)?;
+        let mut stmt = conn.prepare("INSERT INTO user_lesson_list (user_lesson_id, user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name) SELECT row_number() OVER (ORDER BY user_lesson_id) - 1, user_lesson_lesson, user_lesson_timelen, user_lesson_tokenlen, user_lesson_strokesnum, user_lesson_errornum, user_lesson_timestamp, user_lesson_type, user_lesson_name FROM temp_table").map_err(SQLiteError::RusqliteError)?;
+        stmt.execute(params![])
+            .map_err(SQLiteError::RusqliteError)?;
+        trace!("IDs resetted!");
+
+        conn.execute("DROP TABLE temp_table", params![])
+            .map_err(SQLiteError::RusqliteError)?;
+        trace!("Temporary table dropped!");
+
+        info!("IDs reset completed!");
+        Ok(old_ids)
+    })
+}
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use crate::init_logger;
 
     use super::*;
 
-    fn setup_test_db() -> Connection {
+    pub(crate) fn setup_test_db() -> Connection {
         init_logger();
 
         let conn = Connection::open_in_memory().unwrap();
+        create_lesson_table(&conn);
+
+        trace!("Test Database setup completed!");
+
+        conn
+    }
+
+    /// Create a training lesson that can be appended.
+    pub(crate) fn new_lesson(
+        number: usize,
+        strokes: usize,
+        errors: usize,
+        duration: usize,
+        timestamp: u64,
+    ) -> Lesson {
+        Lesson::new(
+            0,
+            LessonCatalogue::fallback().get(number).unwrap(),
+            timestamp,
+            duration,
+            strokes,
+            errors,
+        )
+    }
+
+    /// Create the lesson table like Tipp10 does.
+    pub(crate) fn create_lesson_table(conn: &Connection) {
         conn.execute(
             "CREATE TABLE user_lesson_list (
                 user_lesson_id INTEGER PRIMARY KEY,
@@ -265,23 +710,28 @@ mod tests {
             params![],
         )
         .unwrap();
-
-        trace!("Test Database setup completed!");
-
-        conn
     }
 
     #[test]
     fn test_append_lesson() {
         let conn = setup_test_db();
-        let result = append_lesson(&conn, 1, 100, 10, 60, 20230101120000);
+        let result = append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        );
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_get_lessons() {
         let conn = setup_test_db();
-        append_lesson(&conn, 1, 100, 10, 60, 20230101120000).unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
         let lessons = get_lessons(&conn).unwrap();
         info!("{:?}", lessons);
         assert_eq!(lessons.len(), 1);
@@ -290,35 +740,155 @@ mod tests {
     #[test]
     fn test_update_lesson() {
         let conn = setup_test_db();
-        append_lesson(&conn, 1, 100, 10, 60, 20230101120000).unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
         let result = update_lesson(
             &conn,
-            get_last_lesson_id(&conn).unwrap(),
-            1,
-            200,
-            10,
-            60,
-            20230101120000,
+            &mut Session::default(),
+            &Lesson::new(
+                get_last_lesson_id(&conn).unwrap(),
+                LessonCatalogue::fallback().get(1).unwrap(),
+                20230101120000,
+                60,
+                200,
+                10,
+            ),
         );
         assert!(result.is_ok());
         let lessons = get_lessons(&conn).unwrap();
         assert_eq!(lessons[0].strokes, 200);
     }
 
+    #[test]
+    fn test_update_keeps_lesson_type() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO user_lesson_list VALUES (0, '101', 60, 100, 100, 10, '20230101120000', 1, 'Lesson 1 (asdf jkl;)'), (1, '101', 60, 100, 100, 10, '20230101120000', 7, 'Lesson 1 (asdf jkl;)')",
+            params![],
+        )
+        .unwrap();
+
+        for mut lesson in get_lessons(&conn).unwrap() {
+            lesson.strokes = 200;
+            update_lesson(&conn, &mut Session::default(), &lesson).unwrap();
+        }
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons[0].lesson_type, LessonType::OpenText);
+        assert_eq!(lessons[1].lesson_type, LessonType::Unknown(7));
+        assert_eq!(lessons[1].strokes, 200);
+    }
+
+    #[test]
+    fn test_tokens_are_kept() {
+        let conn = setup_test_db();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 120, 10, 60, 20230101120000).with_tokens(100),
+        )
+        .unwrap();
+
+        let mut lesson = get_lesson(&conn, 0).unwrap();
+        assert_eq!((lesson.strokes, lesson.tokens), (120, 100));
+
+        lesson.strokes = 130;
+        update_lesson(&conn, &mut Session::default(), &lesson).unwrap();
+        let tokens: usize = conn
+            .query_row(
+                "SELECT user_lesson_tokenlen FROM user_lesson_list WHERE user_lesson_id = 0",
+                params![],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(tokens, 100);
+    }
+
+    #[test]
+    fn test_update_keeps_unknown_lesson() {
+        let conn = setup_test_db();
+        conn.execute(
+            "INSERT INTO user_lesson_list VALUES (0, '103', 60, 100, 100, 10, '20230101120000', 0, 'Lektion 3 (ti)'), (1, 'own-7', 60, 100, 100, 10, '20230101120000', 2, 'Meine Lektion')",
+            params![],
+        )
+        .unwrap();
+
+        for mut lesson in get_lessons(&conn).unwrap() {
+            lesson.errors = 5;
+            update_lesson(&conn, &mut Session::default(), &lesson).unwrap();
+        }
+        let mut stmt = conn
+            .prepare("SELECT user_lesson_lesson, user_lesson_name, user_lesson_errornum FROM user_lesson_list ORDER BY user_lesson_id")
+            .unwrap();
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, usize>(2)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<(String, String, usize)>, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("103".to_string(), "Lektion 3 (ti)".to_string(), 5),
+                ("own-7".to_string(), "Meine Lektion".to_string(), 5),
+            ]
+        );
+
+        // Choosing a lesson replaces the stored values
+        let mut lesson = get_lesson(&conn, 0).unwrap();
+        assert_eq!(lesson.number, Some(3));
+        assert_eq!(get_lesson(&conn, 1).unwrap().number, None);
+        lesson.set_catalogue_lesson(LessonCatalogue::fallback().get(4).unwrap());
+        update_lesson(&conn, &mut Session::default(), &lesson).unwrap();
+        assert_eq!(get_lesson(&conn, 0).unwrap().name, "Lesson 4 (nr)");
+    }
+
     #[test]
     fn test_delete_lesson() {
         let conn = setup_test_db();
-        append_lesson(&conn, 1, 100, 10, 60, 20230101120000).unwrap();
-        let result = delete_lesson(&conn, 0);
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        let result = delete_lesson(&conn, &mut Session::default(), 0);
         assert!(result.is_ok());
         let lessons = get_lessons(&conn).unwrap();
         assert!(lessons.is_empty());
     }
 
+    #[test]
+    fn test_delete_missing_lesson() {
+        let conn = setup_test_db();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        let result = delete_lesson(&conn, &mut Session::default(), 5);
+        assert!(matches!(result, Err(SQLiteError::LessonNotFound(5))));
+        assert_eq!(get_lessons(&conn).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_get_last_lesson_id() {
         let conn = setup_test_db();
-        append_lesson(&conn, 1, 100, 10, 60, 20230101120000).unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
         let last_id = get_last_lesson_id(&conn).unwrap();
         assert_eq!(last_id, 0);
     }
@@ -326,14 +896,374 @@ mod tests {
     #[test]
     fn test_reset_ids() {
         let conn = setup_test_db();
-        append_lesson(&conn, 1, 100, 10, 60, 20230101120000).unwrap();
-        append_lesson(&conn, 2, 200, 20, 120, 20230101130000).unwrap();
-        reset_ids(&conn).unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(2, 200, 20, 120, 20230101130000),
+        )
+        .unwrap();
+        reset_ids(&conn, &mut Session::default()).unwrap();
         let lessons = get_lessons(&conn).unwrap();
         assert_eq!(lessons[0].id, 0);
         assert_eq!(lessons[1].id, 1);
     }
 
+    #[test]
+    fn test_has_id_gaps() {
+        let conn = setup_test_db();
+        assert!(!has_id_gaps(&conn).unwrap());
+        append_lesson(
+            &conn,
+            &mut Session::new(IdMode::Stable, 0),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::new(IdMode::Stable, 0),
+            &new_lesson(2, 200, 20, 120, 20230101130000),
+        )
+        .unwrap();
+        assert!(!has_id_gaps(&conn).unwrap());
+        delete_lesson(&conn, &mut Session::new(IdMode::Stable, 0), 0).unwrap();
+        assert!(has_id_gaps(&conn).unwrap());
+    }
+
+    #[test]
+    fn test_stable_ids() {
+        let conn = setup_test_db();
+        for i in 0..4 {
+            append_lesson(
+                &conn,
+                &mut Session::new(IdMode::Stable, 0),
+                &new_lesson(1, 100, 10, 60, 20230101120000 + i),
+            )
+            .unwrap();
+        }
+        delete_lesson(&conn, &mut Session::new(IdMode::Stable, 0), 1).unwrap();
+
+        // The remaining lessons keep their IDs and new lessons are appended after the highest ID
+        let id = append_lesson(
+            &conn,
+            &mut Session::new(IdMode::Stable, 0),
+            &new_lesson(2, 100, 10, 60, 20230101130000),
+        )
+        .unwrap();
+        assert_eq!(id, 4);
+        let ids = get_lessons(&conn)
+            .unwrap()
+            .iter()
+            .map(|lesson| lesson.id)
+            .collect::<Vec<usize>>();
+        assert_eq!(ids, vec![0, 2, 3, 4]);
+
+        // Compacting on request renumbers in the same order
+        reset_ids(&conn, &mut Session::default()).unwrap();
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons[1].timestamp, 20230101120002);
+        assert_eq!(lessons[3].id, 3);
+    }
+
+    #[test]
+    fn test_tipp10_reads_stable_ids() {
+        let conn = setup_test_db();
+        for i in 0..3 {
+            append_lesson(
+                &conn,
+                &mut Session::new(IdMode::Stable, 0),
+                &new_lesson(1, 100, 10, 60, 20230101120000 + i),
+            )
+            .unwrap();
+        }
+        delete_lesson(&conn, &mut Session::new(IdMode::Stable, 0), 1).unwrap();
+
+        // Tipp10 lets SQLite choose the ID when it saves a lesson
+        conn.execute(
+            "INSERT INTO user_lesson_list VALUES (NULL, '101', 60, 100, 100, 10, '20230101130000', 0, 'Lesson 1 (asdf jkl;)')",
+            params![],
+        )
+        .unwrap();
+
+        // Tipp10 reads the lessons ordered by their ID
+        let mut stmt = conn
+            .prepare("SELECT user_lesson_id, user_lesson_timestamp FROM user_lesson_list ORDER BY user_lesson_id")
+            .unwrap();
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((row.get::<_, usize>(0)?, row.get::<_, String>(1)?))
+            })
+            .unwrap()
+            .collect::<Result<Vec<(usize, String)>, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (0, "20230101120000".to_string()),
+                (2, "20230101120002".to_string()),
+                (3, "20230101130000".to_string()),
+            ]
+        );
+
+        let integrity: String = conn
+            .query_row("PRAGMA integrity_check", params![], |row| row.get(0))
+            .unwrap();
+        assert_eq!(integrity, "ok");
+    }
+
+    /// Make every insert into the lesson table fail.
+    fn inject_insert_failure(conn: &Connection) {
+        conn.execute(
+            "CREATE TRIGGER fail_insert BEFORE INSERT ON user_lesson_list BEGIN SELECT RAISE(ABORT, 'Injected failure'); END",
+            params![],
+        )
+        .unwrap();
+    }
+
+    fn get_rows(conn: &Connection) -> Vec<(usize, usize, String)> {
+        get_lessons(conn)
+            .unwrap()
+            .iter()
+            .map(|lesson| (lesson.id, lesson.strokes, lesson.timestamp.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_delete_lesson_rolls_back() {
+        let conn = setup_test_db();
+        for i in 0..3 {
+            append_lesson(
+                &conn,
+                &mut Session::default(),
+                &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+            )
+            .unwrap();
+        }
+        let rows = get_rows(&conn);
+
+        // The lesson is deleted, but renumbering the remaining lessons fails
+        inject_insert_failure(&conn);
+        assert!(delete_lesson(&conn, &mut Session::default(), 0).is_err());
+        assert_eq!(get_rows(&conn), rows);
+    }
+
+    #[test]
+    fn test_append_lesson_rolls_back() {
+        let conn = setup_test_db();
+        for i in 0..3 {
+            append_lesson(
+                &conn,
+                &mut Session::new(IdMode::Stable, 0),
+                &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+            )
+            .unwrap();
+        }
+        delete_lesson(&conn, &mut Session::new(IdMode::Stable, 0), 1).unwrap();
+        let rows = get_rows(&conn);
+
+        // The lesson is inserted, but closing the gap in the IDs fails
+        conn.execute(
+            "CREATE TRIGGER fail_delete BEFORE DELETE ON user_lesson_list BEGIN SELECT RAISE(ABORT, 'Injected failure'); END",
+            params![],
+        )
+        .unwrap();
+        assert!(append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(2, 500, 10, 60, 20230101130000)
+        )
+        .is_err());
+        assert_eq!(get_rows(&conn), rows);
+    }
+
+    #[test]
+    fn test_reset_ids_rolls_back() {
+        let conn = setup_test_db();
+        for i in 0..3 {
+            append_lesson(
+                &conn,
+                &mut Session::new(IdMode::Stable, 0),
+                &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+            )
+            .unwrap();
+        }
+        delete_lesson(&conn, &mut Session::new(IdMode::Stable, 0), 0).unwrap();
+        let rows = get_rows(&conn);
+
+        inject_insert_failure(&conn);
+        assert!(reset_ids(&conn, &mut Session::default()).is_err());
+        assert_eq!(get_rows(&conn), rows);
+
+        // The temporary table is rolled back as well, so the next reset works
+        conn.execute("DROP TRIGGER fail_insert", params![]).unwrap();
+        reset_ids(&conn, &mut Session::default()).unwrap();
+        assert_eq!(get_lessons(&conn).unwrap()[0].id, 0);
+    }
+
+    #[test]
+    fn test_atomic_nested() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let result: Result<(), SQLiteError> = atomic_batch(&conn, &mut session, |session| {
+            append_lesson(&conn, session, &new_lesson(1, 100, 10, 60, 20230101120000))?;
+            Err(SQLiteError::NoLessons("Injected failure".to_string()))
+        });
+        assert!(result.is_err());
+        assert!(get_lessons(&conn).unwrap().is_empty());
+
+        // The rolled back append is not in the history
+        assert_eq!(undo(&conn, &mut session).unwrap(), None);
+
+        // The writes of a successful batch are undone together
+        atomic_batch(&conn, &mut session, |session| {
+            for i in 0..2 {
+                append_lesson(
+                    &conn,
+                    session,
+                    &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(get_lessons(&conn).unwrap().len(), 2);
+        undo(&conn, &mut session).unwrap();
+        assert!(get_lessons(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        for i in 0..4 {
+            append_lesson(
+                &conn,
+                &mut session,
+                &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+            )
+            .unwrap();
+        }
+        let rows = get_rows(&conn);
+
+        // Both deletes renumber the lessons after them
+        delete_lesson(&conn, &mut session, 1).unwrap();
+        delete_lesson(&conn, &mut session, 1).unwrap();
+        let mut lesson = get_lesson(&conn, 1).unwrap();
+        lesson.strokes = 500;
+        update_lesson(&conn, &mut session, &lesson).unwrap();
+        let changed_rows = get_rows(&conn);
+        assert_eq!(
+            changed_rows,
+            vec![
+                (0, 100, "20230101120000".to_string()),
+                (1, 500, "20230101120000".to_string())
+            ]
+        );
+
+        assert_eq!(undo(&conn, &mut session).unwrap(), Some(1));
+        assert_eq!(undo(&conn, &mut session).unwrap(), Some(1));
+        assert_eq!(undo(&conn, &mut session).unwrap(), Some(1));
+        assert_eq!(get_rows(&conn), rows);
+
+        for _ in 0..3 {
+            redo(&conn, &mut session).unwrap();
+        }
+        assert_eq!(get_rows(&conn), changed_rows);
+        assert_eq!(redo(&conn, &mut session).unwrap(), None);
+
+        // Undoing the appends leaves an empty table
+        for _ in 0..7 {
+            undo(&conn, &mut session).unwrap();
+        }
+        assert!(get_lessons(&conn).unwrap().is_empty());
+        assert_eq!(undo(&conn, &mut session).unwrap(), None);
+    }
+
+    #[test]
+    fn test_undo_starting_with_id_gaps() {
+        let conn = setup_test_db();
+        for (id, strokes) in [(0, 100), (2, 102), (5, 105)] {
+            let lesson = Lesson {
+                id,
+                ..new_lesson(1, strokes, 10, 60, 20230101120000)
+            };
+            insert_lesson(&conn, &lesson).unwrap();
+        }
+        let mut session = Session::default();
+        let strokes = |conn: &Connection| {
+            get_rows(conn)
+                .iter()
+                .map(|row| (row.0, row.1))
+                .collect::<Vec<(usize, usize)>>()
+        };
+
+        // The update is recorded before the first compaction
+        let lesson = Lesson {
+            id: 5,
+            ..new_lesson(1, 500, 10, 60, 20230101120000)
+        };
+        update_lesson(&conn, &mut session, &lesson).unwrap();
+        delete_lesson(&conn, &mut session, 2).unwrap();
+        append_lesson(
+            &conn,
+            &mut session,
+            &new_lesson(1, 300, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        assert_eq!(strokes(&conn), vec![(0, 100), (1, 500), (2, 300)]);
+
+        // The history followed the new IDs
+        for _ in 0..3 {
+            undo(&conn, &mut session).unwrap();
+        }
+        assert_eq!(strokes(&conn), vec![(0, 100), (1, 102), (2, 105)]);
+        for _ in 0..3 {
+            redo(&conn, &mut session).unwrap();
+        }
+        assert_eq!(strokes(&conn), vec![(0, 100), (1, 500), (2, 300)]);
+    }
+
+    #[test]
+    fn test_undo_after_reset_ids() {
+        let conn = setup_test_db();
+        let mut session = Session::new(IdMode::Stable, 0);
+        for i in 0..3 {
+            append_lesson(
+                &conn,
+                &mut session,
+                &new_lesson(1, 100 + i, 10, 60, 20230101120000),
+            )
+            .unwrap();
+        }
+        delete_lesson(&conn, &mut session, 1).unwrap();
+        delete_lesson(&conn, &mut session, 0).unwrap();
+
+        // The history follows the IDs when the lessons are compacted on request
+        reset_ids(&conn, &mut session).unwrap();
+        assert_eq!(undo(&conn, &mut session).unwrap(), Some(0));
+        let strokes = get_rows(&conn)
+            .iter()
+            .map(|row| row.1)
+            .collect::<Vec<usize>>();
+        assert_eq!(strokes, vec![100, 102]);
+
+        // A failed undo stays in the history
+        inject_insert_failure(&conn);
+        assert!(undo(&conn, &mut session).is_err());
+        conn.execute("DROP TRIGGER fail_insert", params![]).unwrap();
+        assert_eq!(undo(&conn, &mut session).unwrap(), Some(1));
+        let strokes = get_rows(&conn)
+            .iter()
+            .map(|row| row.1)
+            .collect::<Vec<usize>>();
+        assert_eq!(strokes, vec![100, 101, 102]);
+    }
+
     #[test]
     fn test_get_timestamp() {
         let timestamp = get_timestamp();
@@ -346,4 +1276,11 @@ mod tests {
         let timestamp = get_datetime_tipp10_format_from_str(datetime_str).unwrap();
         assert_eq!(timestamp, 20230101120000);
     }
+
+    #[test]
+    fn test_get_datetime_from_timestamp() {
+        let datetime = get_datetime_from_timestamp(20230101120000).unwrap();
+        assert_eq!(get_timestamp_from_now(&datetime), 20230101120000);
+        assert!(get_datetime_from_timestamp(20231301120000).is_none());
+    }
 }
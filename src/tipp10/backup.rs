@@ -0,0 +1,387 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{error, info, trace, warn};
+use rusqlite::{params, Connection, DatabaseName, OpenFlags};
+
+use super::{get_timestamp, History, SQLiteError, Session};
+
+/// Name of the directory next to the database that holds the backups.
+const BACKUP_DIR: &str = "backups";
+
+/// A backup of the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub timestamp: u64,
+    /// Number of lessons in the backup. None if the backup could not be read.
+    pub lessons: Option<usize>,
+}
+impl BackupInfo {
+    /// Get the file name of the backup.
+    pub fn get_file_name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Get the path of the database file and the backup directory next to it.
+fn get_paths(conn: &Connection) -> Result<(PathBuf, PathBuf), SQLiteError> {
+    let db_path = match conn.path() {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => return Err(SQLiteError::Backup("The database has no file!".to_string())),
+    };
+
+    let backup_dir = db_path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR);
+
+    Ok((db_path, backup_dir))
+}
+
+/// Get the file name prefix of the backups of a database.
+fn get_prefix(db_path: &Path) -> String {
+    let stem = db_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    format!("{}-", stem)
+}
+
+/// Get the timestamp and the number of a backup from its file name. Backups made in the same second
+/// are numbered from 1 after the first one. Names that can not be parsed are the oldest.
+fn get_backup_age(path: &Path, prefix: &str) -> (u64, usize) {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = name
+        .strip_prefix(prefix)
+        .and_then(|name| name.strip_suffix(".db"))
+        .unwrap_or_default();
+
+    let (timestamp, n) = stem.split_once('-').unwrap_or((stem, "0"));
+    match (timestamp.parse(), n.parse()) {
+        (Ok(timestamp), Ok(n)) => (timestamp, n),
+        _ => (0, 0),
+    }
+}
+
+/// Back up the database with the SQLite online backup API and keep only the newest `keep` backups.
+/// The protected backup is never removed.
+pub fn create_backup(
+    conn: &Connection,
+    keep: usize,
+    protected: Option<&Path>,
+) -> Result<PathBuf, SQLiteError> {
+    let (db_path, backup_dir) = get_paths(conn)?;
+    fs::create_dir_all(&backup_dir).map_err(SQLiteError::Io)?;
+
+    // Never overwrite a backup made in the same second
+    let name = format!("{}{}", get_prefix(&db_path), get_timestamp());
+    let mut backup_path = backup_dir.join(format!("{}.db", name));
+    let mut n = 1;
+    while backup_path.exists() {
+        backup_path = backup_dir.join(format!("{}-{}.db", name, n));
+        n += 1;
+    }
+
+    match conn.backup(DatabaseName::Main, &backup_path, None) {
+        Ok(_) => trace!("Backup created: {}", backup_path.display()),
+        Err(e) => {
+            error!("Could not back up database! Error: {}", e);
+            return Err(SQLiteError::RusqliteError(e));
+        }
+    };
+
+    rotate_backups(conn, keep, protected)?;
+
+    info!("Backup completed!");
+    Ok(backup_path)
+}
+
+/// Remove the oldest backups so only `keep` backups are left. The protected backup is never
+/// removed, so it is kept in addition to the others.
+fn rotate_backups(
+    conn: &Connection,
+    keep: usize,
+    protected: Option<&Path>,
+) -> Result<(), SQLiteError> {
+    let protected = protected.map(|path| fs::canonicalize(path).unwrap_or(path.to_path_buf()));
+    for backup in list_backup_files(conn)?.into_iter().skip(keep) {
+        if protected.as_ref().is_some_and(|protected| {
+            fs::canonicalize(&backup).is_ok_and(|backup| backup == *protected)
+        }) {
+            trace!("Backup to restore kept: {}", backup.display());
+            continue;
+        };
+
+        match fs::remove_file(&backup) {
+            Ok(_) => trace!("Old backup removed: {}", backup.display()),
+            Err(e) => warn!(
+                "Could not remove old backup {}! Error: {}",
+                backup.display(),
+                e
+            ),
+        };
+    }
+
+    Ok(())
+}
+
+/// Get the backup files of the database, newest first.
+fn list_backup_files(conn: &Connection) -> Result<Vec<PathBuf>, SQLiteError> {
+    let (db_path, backup_dir) = get_paths(conn)?;
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    };
+
+    let prefix = get_prefix(&db_path);
+    let mut backups = fs::read_dir(&backup_dir)
+        .map_err(SQLiteError::Io)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".db"))
+        })
+        .collect::<Vec<PathBuf>>();
+
+    // Sort by the timestamp and then the number in the name, so `-2` is older than `-10`
+    backups.sort_by_key(|path| std::cmp::Reverse(get_backup_age(path, &prefix)));
+
+    Ok(backups)
+}
+
+/// Get all backups of the database with their lesson counts, newest first.
+pub fn list_backups(conn: &Connection) -> Result<Vec<BackupInfo>, SQLiteError> {
+    let (db_path, _) = get_paths(conn)?;
+    let prefix = get_prefix(&db_path);
+
+    let backups = list_backup_files(conn)?
+        .into_iter()
+        .map(|path| {
+            let (timestamp, _) = get_backup_age(&path, &prefix);
+
+            let lessons = match count_lessons(&path) {
+                Ok(lessons) => Some(lessons),
+                Err(e) => {
+                    warn!("Could not read backup {}! Error: {}", path.display(), e);
+                    None
+                }
+            };
+
+            BackupInfo {
+                path,
+                timestamp,
+                lessons,
+            }
+        })
+        .collect();
+
+    Ok(backups)
+}
+
+/// Count the lessons in a database file.
+fn count_lessons(path: &Path) -> Result<usize, rusqlite::Error> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.query_row("SELECT COUNT(*) FROM user_lesson_list", params![], |row| {
+        row.get(0)
+    })
+}
+
+/// Replace the database with a backup. The current database is backed up first if this is the
+/// first write of the session. The backup to restore is never removed by that backup.
+pub fn restore_backup(
+    conn: &mut Connection,
+    session: &mut Session,
+    path: &Path,
+) -> Result<(), SQLiteError> {
+    if !path.is_file() {
+        error!("Could not find backup {}!", path.display());
+        return Err(SQLiteError::Backup(format!(
+            "Backup {} not found!",
+            path.display()
+        )));
+    };
+
+    session.before_restore(conn, path)?;
+
+    match conn.restore(
+        DatabaseName::Main,
+        path,
+        None::<fn(rusqlite::backup::Progress)>,
+    ) {
+        Ok(_) => (),
+        Err(e) => {
+            error!("Could not restore backup! Error: {}", e);
+            return Err(SQLiteError::RusqliteError(e));
+        }
+    };
+
+    // The recorded changes do not belong to the restored lessons
+    session.history = History::default();
+
+    info!("Backup {} restored!", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::tipp10::{
+        append_lesson, get_lessons,
+        tests::{create_lesson_table, new_lesson},
+    };
+
+    use super::*;
+
+    /// Create a database file in a fresh temporary directory.
+    fn setup_file_db(name: &str) -> (PathBuf, Connection) {
+        let dir = env::temp_dir().join(format!("tipp10w-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let conn = Connection::open(dir.join("tipp10v2.db")).unwrap();
+        create_lesson_table(&conn);
+
+        (dir, conn)
+    }
+
+    #[test]
+    fn test_backup_before_first_write() {
+        let (dir, conn) = setup_file_db("first-write");
+        let mut session = Session::default();
+
+        append_lesson(
+            &conn,
+            &mut session,
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        append_lesson(
+            &conn,
+            &mut session,
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+
+        // Only the first write is backed up, so the backup holds the empty database
+        let backups = list_backups(&conn).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].lessons, Some(0));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let (dir, conn) = setup_file_db("rotate");
+
+        for _ in 0..4 {
+            create_backup(&conn, 2, None).unwrap();
+        }
+        let backups = list_backups(&conn).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(backups[0].get_file_name() > backups[1].get_file_name());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_backups_of_the_same_second() {
+        let (dir, conn) = setup_file_db("same-second");
+        let backup_dir = dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir).unwrap();
+
+        // An older backup and twelve backups of the same second, oldest first
+        let mut names = vec!["tipp10v2-20231231120000-5.db".to_string()];
+        names.push("tipp10v2-20240101120000.db".to_string());
+        for n in 1..12 {
+            names.push(format!("tipp10v2-20240101120000-{}.db", n));
+        }
+        for name in &names {
+            fs::write(backup_dir.join(name), "").unwrap();
+        }
+
+        let file_names = |conn: &Connection| {
+            list_backup_files(conn)
+                .unwrap()
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+                .collect::<Vec<String>>()
+        };
+        names.reverse();
+        assert_eq!(file_names(&conn), names);
+
+        // The newest backups are kept
+        rotate_backups(&conn, 3, None).unwrap();
+        assert_eq!(file_names(&conn), names[..3].to_vec());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let (dir, mut conn) = setup_file_db("restore");
+        let mut session = Session::new(Default::default(), 0);
+        append_lesson(
+            &conn,
+            &mut session,
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        let backup = create_backup(&conn, 5, None).unwrap();
+        append_lesson(
+            &conn,
+            &mut session,
+            &new_lesson(2, 200, 20, 120, 20230101130000),
+        )
+        .unwrap();
+
+        restore_backup(&mut conn, &mut session, &backup).unwrap();
+        assert_eq!(get_lessons(&conn).unwrap().len(), 1);
+        assert!(restore_backup(&mut conn, &mut session, &dir.join("missing.db")).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_oldest_backup() {
+        let (dir, mut conn) = setup_file_db("restore-oldest");
+        let backup_dir = dir.join(BACKUP_DIR);
+        fs::create_dir_all(&backup_dir).unwrap();
+
+        // The backups are full and the oldest one holds a lesson
+        append_lesson(
+            &conn,
+            &mut Session::new(Default::default(), 0),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        let oldest = backup_dir.join("tipp10v2-20230101120000.db");
+        conn.backup(DatabaseName::Main, &oldest, None).unwrap();
+        for n in 1..3 {
+            fs::copy(
+                &oldest,
+                backup_dir.join(format!("tipp10v2-20230101120000-{}.db", n)),
+            )
+            .unwrap();
+        }
+        conn.execute("DELETE FROM user_lesson_list", params![])
+            .unwrap();
+
+        // The first write of the session backs up the database but keeps the chosen backup
+        let mut session = Session::new(Default::default(), 3);
+        restore_backup(&mut conn, &mut session, &oldest).unwrap();
+        assert_eq!(get_lessons(&conn).unwrap().len(), 1);
+        assert!(oldest.exists());
+        assert_eq!(list_backup_files(&conn).unwrap().len(), 4);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
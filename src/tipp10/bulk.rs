@@ -0,0 +1,231 @@
+use chrono::TimeDelta;
+use log::{info, warn};
+use rusqlite::Connection;
+
+use super::{
+    atomic, compact_before_write, get_compacted_id, get_lesson, get_naive_datetime_from_tipp10_str,
+    remove_lessons, validation, write_lesson, CatalogueLesson, Lesson, Operation, SQLiteError,
+    Session,
+};
+
+/// A change that is applied to many lessons at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkAction {
+    /// Delete the lessons.
+    Delete,
+    /// Change the lessons to a lesson of the catalogue.
+    SetLesson(CatalogueLesson),
+    /// Move the timestamps by the offset.
+    ShiftTimestamps(TimeDelta),
+    /// Multiply the durations by the factor.
+    ScaleDurations(f64),
+}
+
+/// Apply the action to the lessons with the IDs in one transaction. Either every lesson is
+/// changed or none. The changes are undone together.
+///
+/// Returns the number of lessons that changed. Lessons that stay the same or whose timestamp can
+/// not be shifted are left out. Nothing is changed if a changed lesson breaks the validation rules.
+pub fn apply_bulk_action(
+    conn: &Connection,
+    session: &mut Session,
+    ids: &[usize],
+    action: &BulkAction,
+) -> Result<usize, SQLiteError> {
+    session.before_write(conn)?;
+    let old_ids = compact_before_write(conn, session)?;
+
+    let mut ids = ids
+        .iter()
+        .map(|id| get_compacted_id(&old_ids, *id))
+        .collect::<Vec<usize>>();
+    ids.sort_unstable();
+    ids.dedup();
+
+    let id_mode = session.id_mode;
+    let validation_rules = session.validation_rules;
+    let operations = atomic(conn, || {
+        if let BulkAction::Delete = action {
+            return remove_lessons(conn, id_mode, &ids);
+        };
+
+        let mut operations = Vec::new();
+        for id in &ids {
+            let before = get_lesson(conn, *id)?;
+            let after = match change_lesson(&before, action) {
+                Some(after) if after != before => after,
+                _ => continue,
+            };
+            validation::check_lesson(&after, &validation_rules)?;
+            write_lesson(conn, &after)?;
+
+            operations.push(Operation::Update { before, after });
+        }
+
+        Ok(operations)
+    })?;
+
+    let count = operations.len();
+    info!("Bulk action completed! Lessons changed: {}", count);
+    if count > 0 {
+        session.history.record(Operation::Batch(operations));
+    };
+
+    Ok(count)
+}
+
+/// Get the lesson with the change of the action. None if the timestamp can not be shifted.
+fn change_lesson(lesson: &Lesson, action: &BulkAction) -> Option<Lesson> {
+    let mut lesson = lesson.clone();
+
+    match action {
+        BulkAction::Delete => (),
+        BulkAction::SetLesson(catalogue_lesson) => lesson.set_catalogue_lesson(catalogue_lesson),
+        BulkAction::ShiftTimestamps(offset) => {
+            let shifted = get_naive_datetime_from_tipp10_str(&lesson.timestamp.to_string())
+                .and_then(|datetime| datetime.checked_add_signed(*offset));
+            lesson.timestamp = match shifted {
+                Some(datetime) => datetime.format("%Y%m%d%H%M%S").to_string().parse().ok()?,
+                None => {
+                    warn!("Could not shift the timestamp! ID: {}", lesson.id);
+                    return None;
+                }
+            };
+        }
+        BulkAction::ScaleDurations(factor) => {
+            lesson.duration = (lesson.duration as f64 * factor).round() as usize;
+        }
+    };
+
+    Some(lesson)
+}
+
+/// Parse a factor like `1.5` or `150%`. The factor has to be positive.
+pub fn parse_factor(factor: &str) -> Option<f64> {
+    let factor = factor.trim();
+    let factor = match factor.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f64>().ok()? / 100.0,
+        None => factor.parse::<f64>().ok()?,
+    };
+
+    (factor.is_finite() && factor > 0.0).then_some(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::{
+        append_lesson, delete_lesson,
+        duration::parse_offset,
+        get_lessons, has_id_gaps, redo,
+        tests::{new_lesson, setup_test_db},
+        undo, IdMode, LessonCatalogue,
+    };
+
+    use super::*;
+
+    fn setup_lessons(session: &mut Session) -> Connection {
+        let conn = setup_test_db();
+        for i in 0..5 {
+            append_lesson(
+                &conn,
+                session,
+                &new_lesson(1, 100 + i, 0, 60, 20240301120000 + i as u64),
+            )
+            .unwrap();
+        }
+
+        conn
+    }
+
+    fn get_strokes(conn: &Connection) -> Vec<usize> {
+        get_lessons(conn)
+            .unwrap()
+            .iter()
+            .map(|lesson| lesson.strokes)
+            .collect()
+    }
+
+    #[test]
+    fn test_bulk_delete() {
+        let mut session = Session::default();
+        let conn = setup_lessons(&mut session);
+
+        let count = apply_bulk_action(&conn, &mut session, &[3, 1, 4], &BulkAction::Delete);
+        assert_eq!(count.unwrap(), 3);
+        assert_eq!(get_strokes(&conn), vec![100, 102]);
+        assert!(!has_id_gaps(&conn).unwrap());
+
+        // The whole bulk action is undone at once
+        undo(&conn, &mut session).unwrap();
+        assert_eq!(get_strokes(&conn), vec![100, 101, 102, 103, 104]);
+        redo(&conn, &mut session).unwrap();
+        assert_eq!(get_strokes(&conn), vec![100, 102]);
+    }
+
+    #[test]
+    fn test_bulk_delete_with_id_gaps() {
+        let conn = setup_lessons(&mut Session::new(IdMode::Stable, 0));
+        let mut session = Session::new(IdMode::Stable, 0);
+        delete_lesson(&conn, &mut session, 1).unwrap();
+        delete_lesson(&conn, &mut session, 3).unwrap();
+
+        // The IDs of the gaps are compacted before the first lesson is deleted
+        let mut session = Session::default();
+        let count = apply_bulk_action(&conn, &mut session, &[2, 4], &BulkAction::Delete);
+        assert_eq!(count.unwrap(), 2);
+        assert_eq!(get_strokes(&conn), vec![100]);
+        undo(&conn, &mut session).unwrap();
+        assert_eq!(get_strokes(&conn), vec![100, 102, 104]);
+    }
+
+    #[test]
+    fn test_bulk_update() {
+        let mut session = Session::default();
+        let conn = setup_lessons(&mut session);
+
+        let offset = parse_offset("-1d2h").unwrap();
+        let count = apply_bulk_action(
+            &conn,
+            &mut session,
+            &[0, 1],
+            &BulkAction::ShiftTimestamps(offset),
+        );
+        assert_eq!(count.unwrap(), 2);
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons[0].timestamp, 20240229100000);
+        assert_eq!(lessons[2].timestamp, 20240301120002);
+
+        let catalogue = LessonCatalogue::fallback();
+        let action = BulkAction::SetLesson(catalogue.get(2).unwrap().clone());
+        apply_bulk_action(&conn, &mut session, &[0, 1, 2, 3, 4], &action).unwrap();
+        let action = BulkAction::ScaleDurations(parse_factor("150%").unwrap());
+        apply_bulk_action(&conn, &mut session, &[2], &action).unwrap();
+        let lessons = get_lessons(&conn).unwrap();
+        assert!(lessons.iter().all(|lesson| lesson.number == Some(2)));
+        assert_eq!(lessons[2].duration, 90);
+
+        // Setting the lesson again changes nothing
+        let action = BulkAction::SetLesson(catalogue.get(2).unwrap().clone());
+        assert_eq!(
+            apply_bulk_action(&conn, &mut session, &[0, 1], &action).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_bulk_action_is_atomic() {
+        let mut session = Session::default();
+        let conn = setup_lessons(&mut session);
+
+        let result = apply_bulk_action(&conn, &mut session, &[0, 9], &BulkAction::Delete);
+        assert!(result.is_err());
+        assert_eq!(get_lessons(&conn).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_parse_factor() {
+        assert_eq!(parse_factor("0.5"), Some(0.5));
+        assert_eq!(parse_factor("200 %"), Some(2.0));
+        assert_eq!(parse_factor("-1"), None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+
+use super::{get_naive_datetime_from_tipp10_str, Lesson};
+
+/// Days in a row, from the start to the end including both. Used for streaks of days with a
+/// lesson and for gaps of days without one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateSpan {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+impl DateSpan {
+    /// Get the number of days of the span.
+    pub fn get_days(&self) -> usize {
+        (self.end - self.start).num_days() as usize + 1
+    }
+
+    /// Check if the day is part of the span.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// The practice time of every day with a lesson.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PracticeCalendar {
+    /// The seconds of practice per day.
+    days: BTreeMap<NaiveDate, usize>,
+}
+
+impl PracticeCalendar {
+    /// Sum the durations of the lessons per day. Lessons with an invalid timestamp are left out.
+    pub fn new(lessons: &[Lesson]) -> Self {
+        let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for lesson in lessons {
+            if let Some(datetime) =
+                get_naive_datetime_from_tipp10_str(&lesson.timestamp.to_string())
+            {
+                *days.entry(datetime.date()).or_default() += lesson.duration;
+            };
+        }
+
+        Self { days }
+    }
+
+    /// Get the seconds of practice of the day.
+    pub fn get_seconds(&self, date: NaiveDate) -> usize {
+        self.days.get(&date).copied().unwrap_or(0)
+    }
+
+    /// Get the seconds of practice of the days from start to end.
+    pub fn get_total_seconds(&self, start: NaiveDate, end: NaiveDate) -> usize {
+        self.days
+            .range(start..=end)
+            .map(|(_, seconds)| seconds)
+            .sum()
+    }
+
+    /// Get the number of days with practice from start to end.
+    pub fn get_practice_days(&self, start: NaiveDate, end: NaiveDate) -> usize {
+        self.days.range(start..=end).count()
+    }
+
+    /// Get the years with practice.
+    pub fn get_years(&self) -> Vec<i32> {
+        let mut years = self
+            .days
+            .keys()
+            .map(|date| date.year())
+            .collect::<Vec<i32>>();
+        years.dedup();
+
+        years
+    }
+
+    /// Get every streak of days with a lesson ordered by date.
+    pub fn get_streaks(&self) -> Vec<DateSpan> {
+        let mut streaks: Vec<DateSpan> = Vec::new();
+        for date in self.days.keys() {
+            match streaks.last_mut() {
+                Some(streak) if streak.end.succ_opt() == Some(*date) => streak.end = *date,
+                _ => streaks.push(DateSpan {
+                    start: *date,
+                    end: *date,
+                }),
+            };
+        }
+
+        streaks
+    }
+
+    /// Get the longest streak. The latest one wins a tie.
+    pub fn get_longest_streak(&self) -> Option<DateSpan> {
+        self.get_streaks()
+            .into_iter()
+            .max_by_key(|streak| streak.get_days())
+    }
+
+    /// Get the streak that includes today or yesterday, so it can still be continued today.
+    pub fn get_current_streak(&self, today: NaiveDate) -> Option<DateSpan> {
+        self.get_streaks()
+            .into_iter()
+            .last()
+            .filter(|streak| streak.end == today || streak.end.succ_opt() == Some(today))
+    }
+
+    /// Get every gap of days without a lesson between two streaks ordered by date.
+    pub fn get_gaps(&self) -> Vec<DateSpan> {
+        self.get_streaks()
+            .windows(2)
+            .filter_map(|streaks| {
+                Some(DateSpan {
+                    start: streaks[0].end.succ_opt()?,
+                    end: streaks[1].start.pred_opt()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Get the longest gap. The latest one wins a tie.
+    pub fn get_longest_gap(&self) -> Option<DateSpan> {
+        self.get_gaps().into_iter().max_by_key(|gap| gap.get_days())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::new_lesson;
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn calendar() -> PracticeCalendar {
+        let mut invalid = new_lesson(1, 100, 0, 60, 20240301120000);
+        invalid.timestamp = 20241301120000;
+
+        PracticeCalendar::new(&[
+            new_lesson(1, 100, 0, 60, 20240301080000),
+            new_lesson(1, 100, 0, 120, 20240301200000),
+            new_lesson(1, 100, 0, 60, 20240302120000),
+            new_lesson(1, 100, 0, 60, 20240303120000),
+            new_lesson(1, 100, 0, 60, 20240310120000),
+            new_lesson(1, 100, 0, 60, 20240311120000),
+            invalid,
+        ])
+    }
+
+    #[test]
+    fn test_practice_per_day() {
+        let calendar = calendar();
+        assert_eq!(calendar.get_seconds(date(2024, 3, 1)), 180);
+        assert_eq!(calendar.get_seconds(date(2024, 3, 4)), 0);
+        assert_eq!(
+            calendar.get_total_seconds(date(2024, 3, 1), date(2024, 3, 31)),
+            420
+        );
+        assert_eq!(
+            calendar.get_practice_days(date(2024, 3, 2), date(2024, 3, 10)),
+            3
+        );
+        assert_eq!(calendar.get_years(), vec![2024]);
+    }
+
+    #[test]
+    fn test_streaks_and_gaps() {
+        let calendar = calendar();
+        assert_eq!(
+            calendar.get_streaks(),
+            vec![
+                DateSpan {
+                    start: date(2024, 3, 1),
+                    end: date(2024, 3, 3)
+                },
+                DateSpan {
+                    start: date(2024, 3, 10),
+                    end: date(2024, 3, 11)
+                },
+            ]
+        );
+        assert_eq!(calendar.get_longest_streak().unwrap().get_days(), 3);
+
+        assert!(calendar.get_current_streak(date(2024, 3, 12)).is_some());
+        assert!(calendar.get_current_streak(date(2024, 3, 13)).is_none());
+
+        let gap = calendar.get_longest_gap().unwrap();
+        assert_eq!((gap.start, gap.end), (date(2024, 3, 4), date(2024, 3, 9)));
+        assert_eq!(gap.get_days(), 6);
+    }
+}
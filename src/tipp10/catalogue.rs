@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use log::{info, trace, warn};
+use rusqlite::{params, Connection};
+
+use super::{language::ENGLISH_LESSON_NAMES, Language};
+
+/// The offset between `lesson_list.lesson_id` and `user_lesson_lesson` if no saved lesson shows it.
+pub const DEFAULT_LESSON_OFFSET: i64 = 100;
+
+/// The number of the lesson that new lessons get if the catalogue has it.
+pub const DEFAULT_LESSON_NUMBER: usize = 18;
+
+/// A training lesson of the catalogue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogueLesson {
+    /// The number of the lesson in the catalogue, starting at 1.
+    pub number: usize,
+    /// The value stored in `user_lesson_lesson`.
+    pub user_lesson: String,
+    /// The value stored in `user_lesson_name`.
+    pub name: String,
+}
+
+/// The training lessons that can be chosen for a lesson.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LessonCatalogue {
+    lessons: Vec<CatalogueLesson>,
+    /// The language of the lesson names. None if the lessons were read from the lesson list.
+    language: Option<Language>,
+}
+
+impl Default for LessonCatalogue {
+    fn default() -> Self {
+        Self::fallback()
+    }
+}
+
+impl LessonCatalogue {
+    /// Create a catalogue from lesson IDs and names ordered by their ID.
+    fn from_rows(rows: &[(i64, String)], offset: i64) -> Self {
+        let lessons = rows
+            .iter()
+            .enumerate()
+            .map(|(i, (lesson_id, name))| CatalogueLesson {
+                number: i + 1,
+                user_lesson: (lesson_id + offset).to_string(),
+                name: name.clone(),
+            })
+            .collect();
+
+        Self {
+            lessons,
+            language: None,
+        }
+    }
+
+    /// Get the catalogue of the English Tipp10 build.
+    pub fn fallback() -> Self {
+        Self {
+            language: Some(Language::English),
+            ..Self::from_rows(&get_english_rows(), DEFAULT_LESSON_OFFSET)
+        }
+    }
+
+    /// Get the training lessons of the database. The lessons are read from the lesson list of the
+    /// database, or are the built-in English lessons if the database has no lesson list. The
+    /// offset of the stored values is detected from the saved lessons either way.
+    ///
+    /// If a language is given that the lessons are not in, they are renamed with the names of that
+    /// language found in the database. Without a lesson list the language of the saved lessons is
+    /// used if none is given.
+    pub fn load(conn: &Connection, language: Option<Language>) -> Self {
+        let language = match read_lesson_list(conn) {
+            Ok(rows) if !rows.is_empty() => {
+                info!("Loaded {} lessons from the lesson list!", rows.len());
+                return Self::from_lesson_list(conn, &rows, language);
+            }
+            Ok(_) => {
+                warn!("The lesson list is empty! Using the built-in lessons.");
+                language.or_else(|| detect_language(conn))
+            }
+            Err(e) => {
+                warn!(
+                    "Could not read the lesson list! Using the built-in lessons. Error: {}",
+                    e
+                );
+                language.or_else(|| detect_language(conn))
+            }
+        };
+
+        let rows = get_english_rows();
+        let offset = detect_offset(conn, &rows).unwrap_or(DEFAULT_LESSON_OFFSET);
+        trace!("Lesson offset: {}", offset);
+
+        let mut catalogue = Self {
+            language: Some(Language::English),
+            ..Self::from_rows(&rows, offset)
+        };
+        if let Some(language) = language.filter(|language| *language != Language::English) {
+            catalogue.translate(conn, language);
+        };
+
+        catalogue
+    }
+
+    /// Get the lessons of the lesson list. They are only renamed if the chosen language is not the
+    /// language of the lesson list.
+    fn from_lesson_list(
+        conn: &Connection,
+        rows: &[(i64, String)],
+        language: Option<Language>,
+    ) -> Self {
+        let offset = detect_offset(conn, rows).unwrap_or(DEFAULT_LESSON_OFFSET);
+        trace!("Lesson offset: {}", offset);
+
+        let mut catalogue = Self::from_rows(rows, offset);
+        let names = rows
+            .iter()
+            .map(|(_, name)| name.clone())
+            .collect::<Vec<String>>();
+        match language {
+            Some(language) if Language::detect(&names) != Some(language) => {
+                catalogue.translate(conn, language)
+            }
+            Some(language) => trace!("The lesson list is in the chosen language: {:?}", language),
+            None => (),
+        };
+
+        catalogue
+    }
+
+    /// Name the lessons in the language. The names are the names Tipp10 saved lessons with in that
+    /// language, or the built-in names for English. Lessons without a name in the language keep
+    /// their name.
+    fn translate(&mut self, conn: &Connection, language: Language) {
+        let saved = read_saved_names(conn, language);
+        for lesson in &mut self.lessons {
+            let name = saved.get(&lesson.number).cloned().or_else(|| {
+                language
+                    .get_lesson_names()
+                    .and_then(|names| names.get(lesson.number - 1))
+                    .map(|name| name.to_string())
+            });
+            if let Some(name) = name {
+                lesson.name = name;
+            };
+        }
+
+        trace!("Using the lesson names of the language: {:?}", language);
+        self.language = Some(language);
+    }
+
+    /// Get a lesson by its number.
+    pub fn get(&self, number: usize) -> Option<&CatalogueLesson> {
+        self.lessons.iter().find(|lesson| lesson.number == number)
+    }
+
+    /// Get the lessons ordered by their number.
+    pub fn iter(&self) -> impl Iterator<Item = &CatalogueLesson> {
+        self.lessons.iter()
+    }
+
+    /// Get the lesson that is stored with this `user_lesson_lesson` value.
+    pub fn find(&self, user_lesson: &str) -> Option<&CatalogueLesson> {
+        self.lessons
+            .iter()
+            .find(|lesson| lesson.user_lesson == user_lesson.trim())
+    }
+
+    /// Get a lesson by its name. Names of the built-in lessons of every language are found by
+    /// their number.
+    pub fn find_by_name(&self, name: &str) -> Option<&CatalogueLesson> {
+        self.lessons
+            .iter()
+            .find(|lesson| lesson.name == name.trim())
+            .or_else(|| {
+                Language::ALL
+                    .into_iter()
+                    .find_map(|language| language.get_lesson_number(name))
+                    .and_then(|number| self.get(number))
+            })
+    }
+
+    /// Get the language of the lesson names. None if the lessons were read from the lesson list.
+    pub fn get_language(&self) -> Option<Language> {
+        self.language
+    }
+
+    /// Get the lesson new lessons are created with.
+    pub fn get_default(&self) -> &CatalogueLesson {
+        self.get(DEFAULT_LESSON_NUMBER)
+            .or(self.lessons.last())
+            .expect("The catalogue always has lessons!")
+    }
+
+    /// Get the number of lessons.
+    pub fn len(&self) -> usize {
+        self.lessons.len()
+    }
+
+    /// Check if the catalogue has no lessons.
+    pub fn is_empty(&self) -> bool {
+        self.lessons.is_empty()
+    }
+}
+
+/// Read the IDs and names of the lesson list ordered by their ID.
+fn read_lesson_list(conn: &Connection) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt =
+        conn.prepare("SELECT lesson_id, lesson_name FROM lesson_list ORDER BY lesson_id")?;
+    let rows = stmt
+        .query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<(i64, String)>, rusqlite::Error>>()?;
+
+    Ok(rows)
+}
+
+/// Get the IDs and names of the built-in English lessons.
+fn get_english_rows() -> Vec<(i64, String)> {
+    ENGLISH_LESSON_NAMES
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (i as i64 + 1, name.to_string()))
+        .collect()
+}
+
+/// Read the names that Tipp10 saved training lessons with in the language by the number of the
+/// lesson. The first saved name of every number is used.
+fn read_saved_names(conn: &Connection, language: Language) -> HashMap<usize, String> {
+    let mut names = HashMap::new();
+    for (_, name) in read_saved_lessons(conn) {
+        if let Some(number) = language.get_lesson_number(&name) {
+            names.entry(number).or_insert(name);
+        };
+    }
+
+    names
+}
+
+/// Read the stored values and names of the saved training lessons ordered by their ID.
+fn read_saved_lessons(conn: &Connection) -> Vec<(String, String)> {
+    let mut stmt = match conn.prepare("SELECT user_lesson_lesson, user_lesson_name FROM user_lesson_list WHERE user_lesson_type = 0 ORDER BY user_lesson_id") {
+        Ok(stmt) => stmt,
+        Err(_) => return Vec::new(),
+    };
+    let saved = match stmt.query_map(params![], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    }) {
+        Ok(rows) => rows.filter_map(|row| row.ok()).collect(),
+        Err(_) => Vec::new(),
+    };
+    saved
+}
+
+/// Detect the language of the database from the names of the saved lessons.
+fn detect_language(conn: &Connection) -> Option<Language> {
+    let mut stmt = conn
+        .prepare("SELECT user_lesson_name FROM user_lesson_list")
+        .ok()?;
+    let names = stmt
+        .query_map(params![], |row| row.get::<_, String>(0))
+        .ok()?
+        .filter_map(|name| name.ok())
+        .collect::<Vec<String>>();
+
+    let language = Language::detect(&names);
+    info!("Detected lesson language: {:?}", language);
+    language
+}
+
+/// Find the offset between the lesson list and the saved training lessons by matching their names.
+/// Names of another language are matched by the number of the lesson. The most common offset
+/// wins. None if no saved lesson matches.
+fn detect_offset(conn: &Connection, rows: &[(i64, String)]) -> Option<i64> {
+    let mut offsets: HashMap<i64, usize> = HashMap::new();
+    for (user_lesson, name) in read_saved_lessons(conn) {
+        let user_lesson = match user_lesson.trim().parse::<i64>() {
+            Ok(user_lesson) => user_lesson,
+            Err(_) => continue,
+        };
+
+        let row = rows
+            .iter()
+            .find(|(_, lesson_name)| *lesson_name == name)
+            .or_else(|| {
+                Language::ALL
+                    .into_iter()
+                    .find_map(|language| language.get_lesson_number(&name))
+                    .and_then(|number| rows.get(number - 1))
+            });
+        if let Some((lesson_id, _)) = row {
+            *offsets.entry(user_lesson - lesson_id).or_default() += 1;
+        };
+    }
+
+    offsets
+        .into_iter()
+        .max_by_key(|(offset, count)| (*count, -offset))
+        .map(|(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::setup_test_db;
+
+    use super::*;
+
+    fn create_lesson_list(conn: &Connection) {
+        conn.execute_batch(
+            "CREATE TABLE lesson_list (lesson_id INTEGER PRIMARY KEY, lesson_name TEXT, lesson_description TEXT);
+             INSERT INTO lesson_list VALUES (1, 'Lektion 1 (asdf jklö)', ''), (2, 'Lektion 2 (er)', '');",
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_fallback() {
+        let catalogue = LessonCatalogue::fallback();
+        assert_eq!(catalogue.len(), 20);
+        assert_eq!(catalogue.get(1).unwrap().name, "Lesson 1 (asdf jkl;)");
+        assert_eq!(catalogue.get(20).unwrap().user_lesson, "120");
+        assert!(catalogue.get(21).is_none());
+        assert_eq!(catalogue.find("105").unwrap().name, "Lesson 5 (hc)");
+        assert_eq!(catalogue.find_by_name("Lesson 5 (hc)").unwrap().number, 5);
+        assert!(catalogue.find_by_name("Unknown Lesson").is_none());
+        assert_eq!(catalogue.get_default().number, DEFAULT_LESSON_NUMBER);
+    }
+
+    #[test]
+    fn test_load_without_lesson_list() {
+        let conn = setup_test_db();
+        assert_eq!(
+            LessonCatalogue::load(&conn, None),
+            LessonCatalogue::fallback()
+        );
+
+        // The language and the offset are detected from the saved lessons, whose names are used
+        conn.execute(
+            "INSERT INTO user_lesson_list VALUES (0, '2', 60, 100, 100, 10, '20230101120000', 0, 'Lektion 2 (er)'), (1, '18', 60, 100, 100, 10, '20230101120000', 0, 'Lektion 18 (Alle Zeichen)')",
+            params![],
+        )
+        .unwrap();
+        let catalogue = LessonCatalogue::load(&conn, None);
+        assert_eq!(catalogue.get_language(), Some(Language::German));
+        assert_eq!(catalogue.len(), 20);
+        assert_eq!(catalogue.get(2).unwrap().name, "Lektion 2 (er)");
+        assert_eq!(catalogue.get(6).unwrap().name, "Lesson 6 (Capitalization)");
+        assert_eq!(catalogue.get_default().user_lesson, "18");
+
+        // A chosen language wins over the detected one and keeps the offset
+        let catalogue = LessonCatalogue::load(&conn, Some(Language::English));
+        assert_eq!(catalogue.get(2).unwrap().name, "Lesson 2 (eo)");
+        assert_eq!(catalogue.get(2).unwrap().user_lesson, "2");
+    }
+
+    #[test]
+    fn test_find_localized_name() {
+        let catalogue = LessonCatalogue::fallback();
+        assert_eq!(catalogue.find_by_name("Lektion 3 (ti)").unwrap().number, 3);
+        assert_eq!(catalogue.find_by_name("Leçon 16").unwrap().number, 16);
+        assert!(catalogue.find_by_name("Lektion 21").is_none());
+    }
+
+    #[test]
+    fn test_load_lesson_list() {
+        let conn = setup_test_db();
+        create_lesson_list(&conn);
+
+        // Without saved lessons the default offset is used
+        let catalogue = LessonCatalogue::load(&conn, None);
+        assert_eq!(catalogue.len(), 2);
+        assert_eq!(catalogue.get(2).unwrap().user_lesson, "102");
+        assert_eq!(catalogue.get_default().number, 2);
+
+        // Saved lessons show the offset of this database
+        conn.execute(
+            "INSERT INTO user_lesson_list VALUES (0, '2', 60, 100, 100, 10, '20230101120000', 0, 'Lektion 2 (er)')",
+            params![],
+        )
+        .unwrap();
+        let catalogue = LessonCatalogue::load(&conn, None);
+        assert_eq!(catalogue.find("1").unwrap().name, "Lektion 1 (asdf jklö)");
+        assert_eq!(catalogue.get_language(), None);
+
+        // The lesson list is used for its own language
+        let catalogue = LessonCatalogue::load(&conn, Some(Language::German));
+        assert_eq!(catalogue.get_language(), None);
+        assert_eq!(catalogue.get(1).unwrap().name, "Lektion 1 (asdf jklö)");
+
+        // Another language renames the lessons of the lesson list but keeps their stored values
+        let catalogue = LessonCatalogue::load(&conn, Some(Language::English));
+        assert_eq!(catalogue.get_language(), Some(Language::English));
+        assert_eq!(catalogue.len(), 2);
+        assert_eq!(catalogue.find("1").unwrap().name, "Lesson 1 (asdf jkl;)");
+        assert_eq!(catalogue.find("2").unwrap().name, "Lesson 2 (eo)");
+    }
+}
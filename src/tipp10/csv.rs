@@ -0,0 +1,691 @@
+use std::{fmt::Display, io};
+
+use log::{error, info, trace, warn};
+use rusqlite::Connection;
+
+use super::{
+    append_lesson, atomic_batch, date_input, get_lessons, get_timestamp,
+    validation::{self, Issue, Severity},
+    Lesson, LessonCatalogue, LessonType, SQLiteError, Session,
+};
+
+/// A lesson field that can be stored in a CSV column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvField {
+    Id,
+    Lesson,
+    Name,
+    /// The stored value of `user_lesson_lesson`, so lessons that are not in the catalogue can be
+    /// restored.
+    UserLesson,
+    Timestamp,
+    Duration,
+    Strokes,
+    Errors,
+    Tokens,
+    Type,
+    Cpm,
+    Wpm,
+    ErrorRate,
+    Points,
+}
+impl CsvField {
+    /// Get the field from a column name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "id" => Some(CsvField::Id),
+            "lesson" => Some(CsvField::Lesson),
+            "name" => Some(CsvField::Name),
+            "user_lesson" => Some(CsvField::UserLesson),
+            "timestamp" => Some(CsvField::Timestamp),
+            "duration" => Some(CsvField::Duration),
+            "strokes" => Some(CsvField::Strokes),
+            "errors" => Some(CsvField::Errors),
+            "tokens" => Some(CsvField::Tokens),
+            "type" => Some(CsvField::Type),
+            "cpm" => Some(CsvField::Cpm),
+            "wpm" => Some(CsvField::Wpm),
+            "error_rate" => Some(CsvField::ErrorRate),
+            "points" => Some(CsvField::Points),
+            _ => None,
+        }
+    }
+
+    /// Get the column name of the field.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            CsvField::Id => "id",
+            CsvField::Lesson => "lesson",
+            CsvField::Name => "name",
+            CsvField::UserLesson => "user_lesson",
+            CsvField::Timestamp => "timestamp",
+            CsvField::Duration => "duration",
+            CsvField::Strokes => "strokes",
+            CsvField::Errors => "errors",
+            CsvField::Tokens => "tokens",
+            CsvField::Type => "type",
+            CsvField::Cpm => "cpm",
+            CsvField::Wpm => "wpm",
+            CsvField::ErrorRate => "error_rate",
+            CsvField::Points => "points",
+        }
+    }
+
+    /// Get the value of the field from a lesson.
+    fn get_value(&self, lesson: &Lesson) -> String {
+        match self {
+            CsvField::Id => lesson.id.to_string(),
+            // Lessons that are not in the catalogue have no number
+            CsvField::Lesson => lesson
+                .number
+                .map_or(String::new(), |number| number.to_string()),
+            CsvField::Name => lesson.name.clone(),
+            CsvField::UserLesson => lesson.user_lesson.clone(),
+            CsvField::Timestamp => lesson.timestamp.to_string(),
+            CsvField::Duration => lesson.duration.to_string(),
+            CsvField::Strokes => lesson.strokes.to_string(),
+            CsvField::Errors => lesson.errors.to_string(),
+            CsvField::Tokens => lesson.tokens.to_string(),
+            CsvField::Type => lesson.lesson_type.get_type().to_string(),
+            CsvField::Cpm => format!("{:.1}", lesson.get_cpm()),
+            CsvField::Wpm => format!("{:.1}", lesson.get_wpm()),
+            CsvField::ErrorRate => format!("{:.2}", lesson.get_error_rate()),
+            CsvField::Points => lesson.get_points().to_string(),
+        }
+    }
+}
+
+/// The columns written by the exporter.
+pub const EXPORT_FIELDS: [CsvField; 14] = [
+    CsvField::Id,
+    CsvField::Lesson,
+    CsvField::Name,
+    CsvField::UserLesson,
+    CsvField::Timestamp,
+    CsvField::Duration,
+    CsvField::Strokes,
+    CsvField::Errors,
+    CsvField::Tokens,
+    CsvField::Type,
+    CsvField::Cpm,
+    CsvField::Wpm,
+    CsvField::ErrorRate,
+    CsvField::Points,
+];
+
+/// An error in a single row of an imported CSV file.
+#[derive(Debug, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+impl Display for RowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+/// The result of an import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub errors: Vec<RowError>,
+    /// The rows that were imported although they break the limits of the validation rules.
+    pub warnings: Vec<RowError>,
+}
+
+/// Errors that can occur while exporting or importing CSV files.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    SQLite(SQLiteError),
+    Header(String),
+    /// The import was aborted because of invalid rows and nothing was imported.
+    Rejected(Vec<RowError>),
+}
+impl Display for CsvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "Io Error: {}", e),
+            CsvError::SQLite(e) => write!(f, "{}", e),
+            CsvError::Header(e) => write!(f, "Invalid CSV header! {}", e),
+            CsvError::Rejected(errors) => {
+                write!(f, "Import aborted! {} invalid rows:", errors.len())?;
+                for e in errors {
+                    write!(f, "\n{}", e)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+impl From<io::Error> for CsvError {
+    fn from(e: io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+impl From<SQLiteError> for CsvError {
+    fn from(e: SQLiteError) -> Self {
+        CsvError::SQLite(e)
+    }
+}
+
+/// Write all lessons of the database as CSV. Returns the number of written rows.
+pub fn export_lessons(conn: &Connection, out: &mut impl io::Write) -> Result<usize, CsvError> {
+    let lessons = get_lessons(conn).map_err(SQLiteError::RusqliteError)?;
+
+    write_lessons(&lessons, out)?;
+
+    info!("Exported {} lessons to CSV!", lessons.len());
+    Ok(lessons.len())
+}
+
+/// Write lessons as CSV with a header row.
+pub fn write_lessons(lessons: &[Lesson], out: &mut impl io::Write) -> io::Result<()> {
+    let header = EXPORT_FIELDS
+        .iter()
+        .map(|field| field.get_name().to_string())
+        .collect::<Vec<String>>();
+    write_record(&header, out)?;
+
+    for lesson in lessons {
+        let record = EXPORT_FIELDS
+            .iter()
+            .map(|field| field.get_value(lesson))
+            .collect::<Vec<String>>();
+        write_record(&record, out)?;
+    }
+
+    Ok(())
+}
+
+/// Import lessons from CSV and append them to the database.
+///
+/// The header row is matched against the column names of [`CsvField`]. Columns listed in `mapping`
+/// are mapped to the given field instead. Invalid rows are reported by their line number. If
+/// `all_or_nothing` is set, nothing is imported if any row is invalid or a database error occurs.
+pub fn import_lessons(
+    conn: &Connection,
+    session: &mut Session,
+    input: &str,
+    mapping: &[(String, CsvField)],
+    all_or_nothing: bool,
+) -> Result<ImportReport, CsvError> {
+    let mut records = parse_records(input).into_iter();
+
+    let columns = match records.next() {
+        Some((_, header)) => map_header(&header, mapping)?,
+        None => return Err(CsvError::Header("The file is empty!".to_string())),
+    };
+    trace!("CSV columns: {:?}", columns);
+
+    let catalogue = LessonCatalogue::load(conn, session.language);
+
+    // Parse every row before touching the database
+    let now = get_timestamp();
+    let mut report = ImportReport::default();
+    let mut lessons = Vec::new();
+    for (line, record) in records {
+        // Skip empty lines
+        if record.iter().all(|value| value.trim().is_empty()) {
+            continue;
+        };
+
+        // Lessons that can not have happened are invalid rows as well
+        let lesson = parse_lesson(&columns, &record, &catalogue).and_then(|lesson| {
+            let (errors, warnings) =
+                validation::validate_lesson(&lesson, &session.validation_rules, now)
+                    .into_iter()
+                    .partition::<Vec<Issue>, _>(|issue| issue.severity == Severity::Error);
+            if let Some(error) = errors.first() {
+                return Err(error.message.clone());
+            };
+
+            report
+                .warnings
+                .extend(warnings.into_iter().map(|issue| RowError {
+                    line,
+                    message: issue.message,
+                }));
+            Ok(lesson)
+        });
+        match lesson {
+            Ok(lesson) => lessons.push((line, lesson)),
+            Err(message) => {
+                warn!("Invalid CSV row on line {}! Error: {}", line, message);
+                report.errors.push(RowError { line, message });
+            }
+        };
+    }
+
+    if all_or_nothing && !report.errors.is_empty() {
+        error!(
+            "Import rejected because of {} invalid rows!",
+            report.errors.len()
+        );
+        return Err(CsvError::Rejected(report.errors));
+    };
+
+    // Back up before the savepoint is opened
+    session.before_write(conn)?;
+
+    let import = |session: &mut Session| {
+        for (line, lesson) in lessons {
+            match append_lesson(conn, session, &lesson) {
+                Ok(_) => report.imported += 1,
+                Err(e) if all_or_nothing => return Err(e),
+                Err(e) => report.errors.push(RowError {
+                    line,
+                    message: e.to_string(),
+                }),
+            };
+        }
+
+        Ok(())
+    };
+
+    // In all or nothing mode a database error rolls back every row imported so far and the rows
+    // are undone together
+    if all_or_nothing {
+        atomic_batch(conn, session, import)?;
+    } else {
+        import(session)?;
+    };
+
+    info!("Imported {} lessons from CSV!", report.imported);
+    Ok(report)
+}
+
+/// Map the header row to lesson fields.
+fn map_header(
+    header: &[String],
+    mapping: &[(String, CsvField)],
+) -> Result<Vec<Option<CsvField>>, CsvError> {
+    let columns = header
+        .iter()
+        .map(|name| {
+            mapping
+                .iter()
+                .find(|(column, _)| column.trim() == name.trim())
+                .map(|(_, field)| *field)
+                .or_else(|| CsvField::from_name(name))
+        })
+        .collect::<Vec<Option<CsvField>>>();
+
+    let has = |field: CsvField| columns.contains(&Some(field));
+    if !has(CsvField::Lesson) && !has(CsvField::Name) && !has(CsvField::UserLesson) {
+        return Err(CsvError::Header(
+            "A lesson, name or user_lesson column is required!".to_string(),
+        ));
+    };
+    if !has(CsvField::Strokes) && !has(CsvField::Tokens) {
+        return Err(CsvError::Header(
+            "A strokes or tokens column is required!".to_string(),
+        ));
+    };
+    for field in [CsvField::Timestamp, CsvField::Duration, CsvField::Errors] {
+        if !has(field) {
+            return Err(CsvError::Header(format!(
+                "Missing column: {}",
+                field.get_name()
+            )));
+        };
+    }
+
+    Ok(columns)
+}
+
+/// Parse a lesson from a CSV row. Lessons are looked up in the catalogue. Rows with an empty
+/// lesson, like exported lessons that are not in the catalogue, keep their stored lesson value
+/// and name. The ID of the returned lesson is meaningless.
+fn parse_lesson(
+    columns: &[Option<CsvField>],
+    record: &[String],
+    catalogue: &LessonCatalogue,
+) -> Result<Lesson, String> {
+    if record.len() != columns.len() {
+        return Err(format!(
+            "Expected {} columns but found {}!",
+            columns.len(),
+            record.len()
+        ));
+    };
+
+    let mut lesson = Lesson::new(0, catalogue.get_default(), 0, 0, 0, 0);
+    let mut has_lesson = false;
+    let mut strokes = None;
+    let mut tokens = None;
+    for (field, value) in columns.iter().zip(record) {
+        let value = value.trim();
+        let invalid = |field: &CsvField| format!("Invalid {}: {}", field.get_name(), value);
+
+        match field {
+            // An empty lesson falls back to the stored lesson value or the name
+            Some(CsvField::Lesson) if value.is_empty() => (),
+            Some(CsvField::Lesson) => {
+                let catalogue_lesson = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| catalogue.get(number))
+                    .ok_or_else(|| invalid(&CsvField::Lesson))?;
+                lesson.set_catalogue_lesson(catalogue_lesson);
+                has_lesson = true;
+            }
+            Some(CsvField::Timestamp) => {
+                lesson.timestamp = date_input::get_timestamp_from_input(value)
+                    .map_err(|_| invalid(&CsvField::Timestamp))?;
+            }
+            Some(CsvField::Duration) => {
+                lesson.duration = value.parse().map_err(|_| invalid(&CsvField::Duration))?;
+            }
+            Some(CsvField::Strokes) => {
+                strokes = Some(value.parse().map_err(|_| invalid(&CsvField::Strokes))?);
+            }
+            Some(CsvField::Errors) => {
+                lesson.errors = value.parse().map_err(|_| invalid(&CsvField::Errors))?;
+            }
+            Some(CsvField::Tokens) => {
+                tokens = Some(value.parse().map_err(|_| invalid(&CsvField::Tokens))?);
+            }
+            // The type is optional and can be given as its number or name
+            Some(CsvField::Type) => {
+                lesson.lesson_type = match value.parse::<i64>() {
+                    Ok(n) => LessonType::from_type(n),
+                    Err(_) => {
+                        LessonType::try_from_name(value).ok_or_else(|| invalid(&CsvField::Type))?
+                    }
+                };
+            }
+            // IDs are assigned by the database, the metrics are calculated and unknown columns are
+            // ignored
+            Some(CsvField::Id)
+            | Some(CsvField::Name)
+            | Some(CsvField::UserLesson)
+            | Some(CsvField::Cpm)
+            | Some(CsvField::Wpm)
+            | Some(CsvField::ErrorRate)
+            | Some(CsvField::Points)
+            | None => (),
+        };
+    }
+
+    // If only one of strokes and tokens is given the other one is the same
+    lesson.strokes = strokes.or(tokens).unwrap_or_default();
+    lesson.tokens = tokens.or(strokes).unwrap_or_default();
+
+    // The stored lesson value and the name are only used if there is no lesson
+    let get = |field: CsvField| {
+        columns
+            .iter()
+            .position(|c| *c == Some(field))
+            .map(|i| record[i].trim())
+            .filter(|value| !value.is_empty())
+    };
+    if !has_lesson {
+        if let Some(user_lesson) = get(CsvField::UserLesson) {
+            let name = get(CsvField::Name).unwrap_or(user_lesson);
+            lesson = lesson.with_stored_lesson(user_lesson, name, catalogue);
+        } else if let Some(i) = columns.iter().position(|c| *c == Some(CsvField::Name)) {
+            let catalogue_lesson = catalogue
+                .find_by_name(&record[i])
+                .ok_or_else(|| format!("Unknown lesson name: {}", record[i].trim()))?;
+            lesson.set_catalogue_lesson(catalogue_lesson);
+        };
+    };
+
+    Ok(lesson)
+}
+
+/// Write a single CSV record, quoting values where necessary.
+fn write_record(record: &[String], out: &mut impl io::Write) -> io::Result<()> {
+    let line = record
+        .iter()
+        .map(|value| {
+            if value.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+    writeln!(out, "{}", line)
+}
+
+/// Parse CSV input into records. Each record carries the line number it starts on.
+fn parse_records(input: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut value = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                // A doubled quote is an escaped quote
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    value.push('"');
+                } else {
+                    in_quotes = false;
+                };
+            }
+            '"' if value.is_empty() => in_quotes = true,
+            ',' if !in_quotes => record.push(std::mem::take(&mut value)),
+            '\r' if !in_quotes && chars.peek() == Some(&'\n') => (),
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut value));
+                records.push((record_line, std::mem::take(&mut record)));
+                line += 1;
+                record_line = line;
+            }
+            c => {
+                if c == '\n' {
+                    line += 1;
+                };
+                value.push(c);
+            }
+        };
+    }
+
+    // The last record does not need a trailing newline
+    if !value.is_empty() || !record.is_empty() {
+        record.push(value);
+        records.push((record_line, record));
+    };
+
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::{
+        tests::{new_lesson, setup_test_db},
+        undo,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_parse_records() {
+        let records = parse_records("a,b\r\n\"c,\"\"d\"\"\",\"e\nf\"\ng,h");
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["c,\"d\"".to_string(), "e\nf".to_string()]),
+                (4, vec!["g".to_string(), "h".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let conn = setup_test_db();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(1, 100, 10, 60, 20230101120000),
+        )
+        .unwrap();
+        append_lesson(
+            &conn,
+            &mut Session::default(),
+            &new_lesson(14, 200, 20, 120, 20230101130000)
+                .with_lesson_type(LessonType::Own)
+                .with_tokens(190),
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(export_lessons(&conn, &mut out).unwrap(), 2);
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with(
+            "id,lesson,name,user_lesson,timestamp,duration,strokes,errors,tokens,type,cpm,wpm,error_rate,points\n"
+        ));
+        assert!(csv.contains(
+            "1,14,Lesson 14 (zZ()-),114,20230101130000,120,200,20,190,2,100.0,20.0,10.00,0\n"
+        ));
+
+        let report = import_lessons(&conn, &mut Session::default(), &csv, &[], true).unwrap();
+        assert_eq!(report.imported, 2);
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons.len(), 4);
+        assert_eq!(lessons[3].number, Some(14));
+        assert_eq!(lessons[3].timestamp, 20230101130000);
+        assert_eq!(lessons[3].lesson_type, LessonType::Own);
+        assert_eq!(lessons[3].tokens, 190);
+    }
+
+    #[test]
+    fn test_export_import_own_lesson() {
+        let conn = setup_test_db();
+        let catalogue = LessonCatalogue::fallback();
+        let own = new_lesson(1, 100, 10, 60, 20230101120000)
+            .with_lesson_type(LessonType::Own)
+            .with_stored_lesson("own-7", "Meine Lektion", &catalogue);
+        append_lesson(&conn, &mut Session::default(), &own).unwrap();
+
+        let mut out = Vec::new();
+        export_lessons(&conn, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.contains("0,,Meine Lektion,own-7,20230101120000,"));
+
+        let report = import_lessons(&conn, &mut Session::default(), &csv, &[], true).unwrap();
+        assert_eq!(report.imported, 1);
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons[1].number, None);
+        assert_eq!(lessons[1].user_lesson, "own-7");
+        assert_eq!(lessons[1].name, "Meine Lektion");
+        assert_eq!(lessons[1].lesson_type, LessonType::Own);
+    }
+
+    #[test]
+    fn test_import_with_mapping_and_row_errors() {
+        let conn = setup_test_db();
+        let csv = "Date,Lesson name,Seconds,strokes,errors\n\
+                   2024-03-05 14:30,Lesson 2 (eo),300,900,9\n\
+                   2024-03-06 14:30,Lesson 99,300,900,9\n\
+                   2024-03-07 14:30,Lesson 3 (ti),abc,900,9\n";
+        let mapping = [
+            ("Date".to_string(), CsvField::Timestamp),
+            ("Lesson name".to_string(), CsvField::Name),
+            ("Seconds".to_string(), CsvField::Duration),
+        ];
+
+        let report = import_lessons(&conn, &mut Session::default(), csv, &mapping, false).unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(report.errors[0].line, 3);
+        assert_eq!(report.errors[1].line, 4);
+
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!(lessons[0].number, Some(2));
+        assert_eq!(lessons[0].timestamp, 20240305143000);
+        assert_eq!(lessons[0].tokens, 900);
+    }
+
+    #[test]
+    fn test_import_validation() {
+        let conn = setup_test_db();
+        let csv = "lesson,timestamp,duration,strokes,errors\n\
+                   1,20240305143000,300,900,9\n\
+                   1,20240305143000,300,900,901\n\
+                   1,20240305143000,300,900,600\n\
+                   1,29990101120000,300,900,9\n";
+
+        let report = import_lessons(&conn, &mut Session::default(), csv, &[], false).unwrap();
+        assert_eq!(report.imported, 2);
+        let lines = |rows: &[RowError]| rows.iter().map(|row| row.line).collect::<Vec<usize>>();
+        assert_eq!(lines(&report.errors), vec![3, 5]);
+        assert_eq!(lines(&report.warnings), vec![4]);
+    }
+
+    #[test]
+    fn test_import_tokens_only() {
+        let conn = setup_test_db();
+        let csv = "lesson,timestamp,duration,tokens,errors
+1,20240305143000,300,850,9
+";
+
+        import_lessons(&conn, &mut Session::default(), csv, &[], true).unwrap();
+        let lessons = get_lessons(&conn).unwrap();
+        assert_eq!((lessons[0].strokes, lessons[0].tokens), (850, 850));
+    }
+
+    #[test]
+    fn test_import_all_or_nothing() {
+        let conn = setup_test_db();
+        let csv = "lesson,timestamp,duration,strokes,errors\n\
+                   1,20240305143000,300,900,9\n\
+                   1,20240305143000,300,-5,9\n";
+
+        let result = import_lessons(&conn, &mut Session::default(), csv, &[], true);
+        assert!(matches!(result, Err(CsvError::Rejected(ref errors)) if errors[0].line == 3));
+        assert!(get_lessons(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_all_or_nothing_history() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let csv = "lesson,timestamp,duration,strokes,errors\n\
+                   1,20240305143000,300,900,9\n\
+                   1,20240305143000,300,999,9\n";
+
+        // A database error on the second row rolls back the first one and its history
+        conn.execute(
+            "CREATE TRIGGER fail_insert BEFORE INSERT ON user_lesson_list WHEN NEW.user_lesson_strokesnum = 999 BEGIN SELECT RAISE(ABORT, 'Injected failure'); END",
+            [],
+        )
+        .unwrap();
+        assert!(import_lessons(&conn, &mut session, csv, &[], true).is_err());
+        assert!(get_lessons(&conn).unwrap().is_empty());
+        assert_eq!(undo(&conn, &mut session).unwrap(), None);
+
+        // A successful import is undone at once
+        conn.execute("DROP TRIGGER fail_insert", []).unwrap();
+        let report = import_lessons(&conn, &mut session, csv, &[], true).unwrap();
+        assert_eq!(report.imported, 2);
+        undo(&conn, &mut session).unwrap();
+        assert!(get_lessons(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_missing_column() {
+        let conn = setup_test_db();
+        let result = import_lessons(
+            &conn,
+            &mut Session::default(),
+            "lesson,timestamp\n1,20240305143000\n",
+            &[],
+            false,
+        );
+        assert!(matches!(result, Err(CsvError::Header(_))));
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
+
+use super::{duration::parse_offset, get_naive_datetime_from_tipp10_str, get_timestamp_from_now};
+
+/// The formats of a date without the time.
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d.%m.%Y", "%Y/%m/%d"];
+
+/// The formats of a time without the date.
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S", "%H:%M"];
+
+/// Parse a date and time typed by the user relative to now. Accepted are:
+/// - the tipp10 format `20240305143000` and ISO 8601 like `2024-03-05T14:30:00+01:00`,
+/// - a date like `2024-03-05`, `05.03.2024` or `2024/03/05`, starting at midnight,
+/// - `now`, `today`, `yesterday`, `tomorrow` or an offset like `-2d` or `-1h30m`, keeping the
+///   time of now,
+/// - any of the dates or days followed by a time like `14:30` or `14:30:15`, or only a time for
+///   today.
+pub fn parse_date_input(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let input = input.trim();
+
+    if let Some(datetime) = get_naive_datetime_from_tipp10_str(input) {
+        return Some(datetime);
+    };
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Some(datetime.with_timezone(&Local).naive_local());
+    };
+
+    let mut parts = input.split_whitespace().collect::<Vec<&str>>();
+    // ISO 8601 separates the date and the time with a `T`
+    if let [datetime] = parts.as_slice() {
+        if let Some((date, time)) = datetime
+            .split_once('T')
+            .filter(|(date, _)| date.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            parts = vec![date, time];
+        };
+    };
+    match parts.as_slice() {
+        [day] => match parse_time(day) {
+            Some(time) => Some(now.date().and_time(time)),
+            None => parse_day(day, now),
+        },
+        [day, time] => Some(parse_day(day, now)?.date().and_time(parse_time(time)?)),
+        _ => None,
+    }
+}
+
+/// Parse a date and time typed by the user into a tipp10 timestamp. The time has to exist in
+/// the local time zone. A time that happens twice when daylight saving time ends is the earlier one.
+pub fn get_timestamp_from_input(input: &str) -> Result<u64, ()> {
+    let datetime = parse_date_input(input, Local::now().naive_local()).ok_or(())?;
+
+    match Local.from_local_datetime(&datetime).earliest() {
+        Some(datetime) => Ok(get_timestamp_from_now(&datetime)),
+        None => Err(()),
+    }
+}
+
+/// Parse a date, a named day or an offset from now.
+fn parse_day(day: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    match day.to_lowercase().as_str() {
+        "now" | "today" => return Some(now),
+        "yesterday" => return now.checked_sub_signed(TimeDelta::days(1)),
+        "tomorrow" => return now.checked_add_signed(TimeDelta::days(1)),
+        _ => (),
+    };
+
+    if day.starts_with(['+', '-']) {
+        return now.checked_add_signed(parse_offset(day)?);
+    };
+
+    DATE_FORMATS.iter().find_map(|format| {
+        NaiveDate::parse_from_str(day, format)
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    })
+}
+
+/// Parse a time like `14:30` or `14:30:15`.
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(time, format).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date_input() {
+        let now = get_naive_datetime_from_tipp10_str("20240305143015").unwrap();
+        let parse = |input: &str| {
+            parse_date_input(input, now).map(|datetime| datetime.format("%Y%m%d%H%M%S").to_string())
+        };
+
+        assert_eq!(parse("20240101120000").as_deref(), Some("20240101120000"));
+        assert_eq!(parse("2024-03-01 14:30").as_deref(), Some("20240301143000"));
+        assert_eq!(
+            parse("2024-03-01T14:30:05").as_deref(),
+            Some("20240301143005")
+        );
+        assert_eq!(parse("01.03.2024").as_deref(), Some("20240301000000"));
+        assert_eq!(parse("yesterday 18:00").as_deref(), Some("20240304180000"));
+        assert_eq!(parse("Today").as_deref(), Some("20240305143015"));
+        assert_eq!(parse("-2d").as_deref(), Some("20240303143015"));
+        assert_eq!(parse("-1h30m").as_deref(), Some("20240305130015"));
+        assert_eq!(parse("-1d 9:05").as_deref(), Some("20240304090500"));
+        assert_eq!(parse("8:15").as_deref(), Some("20240305081500"));
+        assert_eq!(parse("2024-02-30"), None);
+        assert_eq!(parse("25:00"), None);
+        assert_eq!(parse("soon"), None);
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::TimeDelta;
+
+/// Parse a duration typed by the user into seconds. Accepted are seconds like `600`, minutes and
+/// seconds like `10:00`, hours, minutes and seconds like `1:05:00` and units like `10m` or `1h30m`.
+pub fn parse_duration(input: &str) -> Option<usize> {
+    let input = input.trim();
+
+    if input.contains(':') {
+        let parts = input
+            .split(':')
+            .map(|part| {
+                // Every part is a number without a sign
+                if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                    return None;
+                };
+                part.parse::<usize>().ok()
+            })
+            .collect::<Option<Vec<usize>>>()?;
+
+        let (hours, minutes, seconds) = match parts.as_slice() {
+            [minutes, seconds] => (0, *minutes, *seconds),
+            [hours, minutes, seconds] if *minutes < 60 => (*hours, *minutes, *seconds),
+            _ => return None,
+        };
+        if seconds >= 60 {
+            return None;
+        };
+
+        return hours
+            .checked_mul(3600)?
+            .checked_add(minutes.checked_mul(60)?)?
+            .checked_add(seconds);
+    };
+
+    if let Ok(seconds) = input.parse::<usize>() {
+        return Some(seconds);
+    };
+
+    // Durations can not be negative, so they have no sign
+    if input.starts_with(['+', '-']) {
+        return None;
+    };
+    usize::try_from(parse_offset(input)?.num_seconds()).ok()
+}
+
+/// Parse an offset like `+1d`, `-2h`, `90m` or `1h30m`. The units are `d`, `h`, `m` and `s`.
+pub fn parse_offset(offset: &str) -> Option<TimeDelta> {
+    let offset = offset.trim();
+    let (sign, rest) = match offset.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, offset.strip_prefix('+').unwrap_or(offset)),
+    };
+    if rest.is_empty() {
+        return None;
+    };
+
+    let mut seconds: i64 = 0;
+    let mut number = String::new();
+    for c in rest.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        };
+
+        let unit = match c {
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        seconds = seconds.checked_add(number.parse::<i64>().ok()?.checked_mul(unit)?)?;
+        number.clear();
+    }
+
+    // Every number needs a unit
+    if !number.is_empty() {
+        return None;
+    };
+
+    TimeDelta::try_seconds(sign * seconds)
+}
+
+/// Format seconds as `mm:ss` or `h:mm:ss` if the duration is an hour or longer.
+pub fn format_duration(seconds: usize) -> String {
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("+90m"), Some(TimeDelta::minutes(90)));
+        assert_eq!(parse_offset("-1h30m"), Some(TimeDelta::minutes(-90)));
+        assert_eq!(parse_offset("2d"), Some(TimeDelta::days(2)));
+        assert_eq!(parse_offset("15"), None);
+        assert_eq!(parse_offset("1w"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("600"), Some(600));
+        assert_eq!(parse_duration("10:00"), Some(600));
+        assert_eq!(parse_duration("125:30"), Some(7530));
+        assert_eq!(parse_duration("1:05:00"), Some(3900));
+        assert_eq!(parse_duration("10m"), Some(600));
+        assert_eq!(parse_duration(" 1h30m "), Some(5400));
+        assert_eq!(parse_duration("90s"), Some(90));
+        assert_eq!(parse_duration("10:60"), None);
+        assert_eq!(parse_duration("1:60:00"), None);
+        assert_eq!(parse_duration("-10m"), None);
+        assert_eq!(parse_duration("10:"), None);
+        assert_eq!(parse_duration("ten"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "00:00");
+        assert_eq!(format_duration(605), "10:05");
+        assert_eq!(format_duration(3900), "1:05:00");
+        for seconds in [59, 600, 3599, 3600, 36000] {
+            assert_eq!(parse_duration(&format_duration(seconds)), Some(seconds));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use super::{duration::parse_duration, sort::SortColumn, Lesson};
+
+/// A comparison of a numeric column with a value, e.g. `errors>50`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+impl Comparison {
+    /// The operators of the comparisons. Longer operators come first so `>=` is not read as `>`.
+    const OPERATORS: [(&'static str, Comparison); 7] = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("!=", Comparison::NotEqual),
+        ("==", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        ("=", Comparison::Equal),
+    ];
+
+    fn matches(&self, a: f64, b: f64) -> bool {
+        match self {
+            Comparison::Less => a < b,
+            Comparison::LessOrEqual => a <= b,
+            Comparison::Equal => a == b,
+            Comparison::NotEqual => a != b,
+            Comparison::GreaterOrEqual => a >= b,
+            Comparison::Greater => a > b,
+        }
+    }
+}
+
+/// A single condition of the filter.
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    /// The name contains the text, ignoring the case.
+    Name(String),
+    /// The timestamp is in the range, both ends included.
+    Date(u64, u64),
+    /// The value of the column compares to the number.
+    Number(SortColumn, Comparison, f64),
+}
+
+/// A filter of the lesson list. Every whitespace separated term of the query has to match:
+/// - `2024`, `2024-03` or `2024-03-15` matches the lessons of the year, month or day and
+///   `2024-01..2024-03` the lessons from the start of the first to the end of the last date,
+/// - `errors>50`, `cpm>=200` or `lesson=18` compares a numeric column with a number,
+/// - any other text has to be part of the lesson name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LessonFilter {
+    query: String,
+    terms: Vec<Term>,
+}
+
+impl LessonFilter {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.trim().to_string(),
+            terms: query.split_whitespace().map(parse_term).collect(),
+        }
+    }
+
+    /// Get the query the filter was created from.
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    /// Check if the filter lets every lesson through.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Check if the lesson matches every term of the filter.
+    pub fn matches(&self, lesson: &Lesson) -> bool {
+        self.terms.iter().all(|term| match term {
+            Term::Name(text) => lesson.name.to_lowercase().contains(text),
+            Term::Date(start, end) => (*start..=*end).contains(&lesson.timestamp),
+            Term::Number(column, comparison, value) => {
+                get_number(column, lesson).is_some_and(|number| comparison.matches(number, *value))
+            }
+        })
+    }
+}
+
+/// Parse a term of the query. Terms that are neither a date nor a comparison search the name.
+fn parse_term(term: &str) -> Term {
+    if let Some((start, end)) = parse_date_range(term) {
+        return Term::Date(start, end);
+    };
+    if let Some((column, comparison, value)) = parse_comparison(term) {
+        return Term::Number(column, comparison, value);
+    };
+
+    Term::Name(term.to_lowercase())
+}
+
+/// Parse a date or a range of dates into the first and the last tipp10 timestamp.
+fn parse_date_range(term: &str) -> Option<(u64, u64)> {
+    match term.split_once("..") {
+        Some((start, end)) => {
+            let (start, _) = parse_date(start)?;
+            let (_, end) = parse_date(end)?;
+            Some((start, end))
+        }
+        None => parse_date(term),
+    }
+}
+
+/// Parse `YYYY`, `YYYY-MM` or `YYYY-MM-DD` into the first and the last tipp10 timestamp of it.
+fn parse_date(date: &str) -> Option<(u64, u64)> {
+    let parts = date.split('-').collect::<Vec<&str>>();
+    let lengths = parts.iter().map(|part| part.len()).collect::<Vec<usize>>();
+    if !matches!(lengths.as_slice(), [4] | [4, 2] | [4, 2, 2])
+        || !parts
+            .iter()
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    };
+
+    let numbers = parts
+        .iter()
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    if numbers
+        .get(1)
+        .is_some_and(|month| !(1..=12).contains(month))
+        || numbers.get(2).is_some_and(|day| !(1..=31).contains(day))
+    {
+        return None;
+    };
+
+    // The digits are the start of the timestamp, the rest is filled with the lowest or highest value
+    let digits = parts.concat();
+    let scale = 10u64.pow(14 - digits.len() as u32);
+    let prefix = digits.parse::<u64>().ok()?;
+
+    Some((prefix * scale, (prefix + 1) * scale - 1))
+}
+
+/// Parse a comparison like `errors>50`.
+fn parse_comparison(term: &str) -> Option<(SortColumn, Comparison, f64)> {
+    let (name, comparison, value) =
+        Comparison::OPERATORS
+            .iter()
+            .find_map(|(operator, comparison)| {
+                let (name, value) = term.split_once(operator)?;
+                Some((name, *comparison, value))
+            })?;
+
+    let column = match name.to_lowercase().as_str() {
+        "id" => SortColumn::Id,
+        "lesson" => SortColumn::Lesson,
+        "timestamp" => SortColumn::Timestamp,
+        "duration" => SortColumn::Duration,
+        "strokes" => SortColumn::Strokes,
+        "errors" => SortColumn::Errors,
+        "tokens" => SortColumn::Tokens,
+        "cpm" => SortColumn::Cpm,
+        "wpm" => SortColumn::Wpm,
+        "error_rate" | "errorrate" => SortColumn::ErrorRate,
+        "points" => SortColumn::Points,
+        _ => return None,
+    };
+
+    // Durations can also be compared with `10:00` or `10m`
+    let value = match column {
+        SortColumn::Duration => parse_duration(value)? as f64,
+        _ => value.trim_end_matches('%').parse().ok()?,
+    };
+
+    Some((column, comparison, value))
+}
+
+/// Get the value of a numeric column. None for columns that are not numeric and lessons that are
+/// not in the catalogue.
+fn get_number(column: &SortColumn, lesson: &Lesson) -> Option<f64> {
+    match column {
+        SortColumn::Id => Some(lesson.id as f64),
+        SortColumn::Lesson => lesson.number.map(|number| number as f64),
+        SortColumn::Timestamp => Some(lesson.timestamp as f64),
+        SortColumn::Duration => Some(lesson.duration as f64),
+        SortColumn::Strokes => Some(lesson.strokes as f64),
+        SortColumn::Errors => Some(lesson.errors as f64),
+        SortColumn::Tokens => Some(lesson.tokens as f64),
+        SortColumn::Cpm => Some(lesson.get_cpm()),
+        SortColumn::Wpm => Some(lesson.get_wpm()),
+        SortColumn::ErrorRate => Some(lesson.get_error_rate()),
+        SortColumn::Points => Some(lesson.get_points() as f64),
+        SortColumn::Name | SortColumn::Type => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::new_lesson;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(
+            parse_date("2024-03"),
+            Some((20240300000000, 20240399999999))
+        );
+        assert_eq!(
+            parse_date_range("2024..2025-01-02"),
+            Some((20240000000000, 20250102999999))
+        );
+        assert_eq!(parse_date("2024-13"), None);
+        assert_eq!(parse_date("24-03"), None);
+        assert_eq!(parse_date("Lesson"), None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let lessons = [
+            new_lesson(1, 100, 60, 60, 20240301120000),
+            new_lesson(18, 300, 2, 60, 20240415120000),
+            new_lesson(18, 200, 0, 60, 20240302120000),
+        ];
+        let count = |query: &str| {
+            let filter = LessonFilter::new(query);
+            lessons
+                .iter()
+                .filter(|lesson| filter.matches(lesson))
+                .count()
+        };
+
+        assert_eq!(count(""), 3);
+        assert_eq!(count("2024-03"), 2);
+        assert_eq!(count("2024-03-02..2024-04"), 2);
+        assert_eq!(count("errors>50"), 1);
+        assert_eq!(count("duration>=1:00"), 3);
+        assert_eq!(count("duration>1m"), 0);
+        assert_eq!(count("lesson=18 cpm>=250"), 1);
+        assert_eq!(count("error_rate<0.5%"), 1);
+        assert_eq!(count("all CHARACTERS"), 2);
+        assert_eq!(count("unknown>5"), 0);
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::BTreeSet;
+
+use log::trace;
+
+use super::Lesson;
+
+/// The maximum number of operations that can be undone.
+pub const HISTORY_LIMIT: usize = 100;
+
+/// A write to the lesson table that can be reverted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// A lesson was appended with the ID of the lesson.
+    Append(Lesson),
+    /// A lesson was changed from `before` to `after`.
+    Update { before: Lesson, after: Lesson },
+    /// A lesson was deleted. The ID is the place the lesson is restored to.
+    Delete(Lesson),
+    /// Operations that were applied together in this order and are undone together.
+    Batch(Vec<Operation>),
+}
+impl Operation {
+    /// Get the operation that reverts this operation.
+    pub fn inverse(&self) -> Self {
+        match self {
+            Operation::Append(lesson) => Operation::Delete(lesson.clone()),
+            Operation::Update { before, after } => Operation::Update {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Operation::Delete(lesson) => Operation::Append(lesson.clone()),
+            Operation::Batch(operations) => {
+                Operation::Batch(operations.iter().rev().map(Operation::inverse).collect())
+            }
+        }
+    }
+
+    /// Get the ID of the lesson the operation changes. A batch returns the ID of its first
+    /// operation or 0 if it is empty.
+    pub fn get_id(&self) -> usize {
+        match self {
+            Operation::Append(lesson) | Operation::Delete(lesson) => lesson.id,
+            Operation::Update { after, .. } => after.id,
+            Operation::Batch(operations) => operations.first().map_or(0, Operation::get_id),
+        }
+    }
+
+    /// Set the ID of every lesson of the operation. A batch is left unchanged, its operations
+    /// are renumbered one by one.
+    fn set_id(&mut self, id: usize) {
+        match self {
+            Operation::Append(lesson) | Operation::Delete(lesson) => lesson.id = id,
+            Operation::Update { before, after } => {
+                before.id = id;
+                after.id = id;
+            }
+            Operation::Batch(_) => (),
+        };
+    }
+
+    /// Apply the operation to the set of IDs in the table.
+    fn apply_to_ids(&self, ids: &mut BTreeSet<usize>) {
+        match self {
+            Operation::Append(lesson) => {
+                ids.insert(lesson.id);
+            }
+            Operation::Delete(lesson) => {
+                ids.remove(&lesson.id);
+            }
+            Operation::Update { .. } => (),
+            Operation::Batch(operations) => {
+                for operation in operations {
+                    operation.apply_to_ids(ids);
+                }
+            }
+        };
+    }
+
+    /// Get the single operations in the order they were applied. Batches are flattened.
+    fn flatten_mut(&mut self) -> Vec<&mut Operation> {
+        match self {
+            Operation::Batch(operations) => operations
+                .iter_mut()
+                .flat_map(Operation::flatten_mut)
+                .collect(),
+            operation => vec![operation],
+        }
+    }
+}
+
+/// The operations of a session that can be undone and redone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct History {
+    undo: Vec<Operation>,
+    redo: Vec<Operation>,
+}
+
+impl History {
+    /// Record a new operation. Operations that were undone can not be redone anymore.
+    pub fn record(&mut self, operation: Operation) {
+        trace!("Operation recorded: {:?}", operation);
+
+        self.undo.push(operation);
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        };
+        self.redo.clear();
+    }
+
+    /// Check if there is an operation to undo.
+    pub(super) fn has_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Check if there is an undone operation to redo.
+    pub(super) fn has_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Take every operation that can be undone, oldest first.
+    pub(super) fn take_undo(self) -> Vec<Operation> {
+        self.undo
+    }
+
+    /// Take the last operation to undo it.
+    pub(super) fn pop_undo(&mut self) -> Option<Operation> {
+        self.undo.pop()
+    }
+
+    /// Take the last undone operation to redo it.
+    pub(super) fn pop_redo(&mut self) -> Option<Operation> {
+        self.redo.pop()
+    }
+
+    /// Put an undone operation on the redo stack.
+    pub(super) fn push_redo(&mut self, operation: Operation) {
+        self.redo.push(operation);
+    }
+
+    /// Put a redone operation back on the undo stack without clearing the redo stack.
+    pub(super) fn push_undo(&mut self, operation: Operation) {
+        self.undo.push(operation);
+    }
+
+    /// Follow a renumbering of the lessons. `old_ids` are the old IDs in the order of the new IDs.
+    ///
+    /// Every operation is applied to the lessons as they are when it is undone or redone, so the
+    /// IDs of those lessons are replayed to find the new place of the lesson of the operation.
+    pub fn remap(&mut self, old_ids: &[usize]) {
+        let new_id = |ids: &BTreeSet<usize>, id: usize| ids.range(..id).count();
+
+        let mut ids = old_ids.iter().copied().collect::<BTreeSet<usize>>();
+        for operation in self.undo.iter_mut().flat_map(Operation::flatten_mut).rev() {
+            let inverse = operation.inverse();
+            operation.set_id(new_id(&ids, operation.get_id()));
+            inverse.apply_to_ids(&mut ids);
+        }
+
+        // The operations of a batch are redone in their order, but the batches in reverse order
+        let mut ids = old_ids.iter().copied().collect::<BTreeSet<usize>>();
+        for operation in self.redo.iter_mut().rev().flat_map(Operation::flatten_mut) {
+            let old_operation = operation.clone();
+            operation.set_id(new_id(&ids, operation.get_id()));
+            old_operation.apply_to_ids(&mut ids);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::LessonCatalogue;
+
+    use super::*;
+
+    fn lesson(id: usize) -> Lesson {
+        Lesson::new(
+            id,
+            LessonCatalogue::fallback().get(1).unwrap(),
+            20230101120000,
+            60,
+            100,
+            10,
+        )
+    }
+
+    #[test]
+    fn test_record_clears_redo() {
+        let mut history = History::default();
+        history.record(Operation::Append(lesson(0)));
+        let operation = history.pop_undo().unwrap();
+        history.push_redo(operation);
+        assert_eq!(history.redo.len(), 1);
+
+        history.record(Operation::Delete(lesson(0)));
+        assert!(history.redo.is_empty());
+        assert_eq!(history.undo.len(), 1);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = History::default();
+        for i in 0..HISTORY_LIMIT + 5 {
+            history.record(Operation::Append(lesson(i)));
+        }
+        assert_eq!(history.undo.len(), HISTORY_LIMIT);
+        assert_eq!(history.undo[0].get_id(), 5);
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut history = History::default();
+        history.record(Operation::Append(lesson(4)));
+        history.record(Operation::Delete(lesson(3)));
+        history.record(Operation::Delete(lesson(1)));
+
+        // Lessons 0, 2 and 4 are renumbered to 0, 1 and 2
+        history.remap(&[0, 2, 4]);
+        assert_eq!(history.pop_undo().unwrap().get_id(), 1);
+        assert_eq!(history.pop_undo().unwrap().get_id(), 3);
+        assert_eq!(history.pop_undo().unwrap().get_id(), 4);
+    }
+
+    #[test]
+    fn test_remap_batch() {
+        let mut history = History::default();
+        history.record(Operation::Append(lesson(4)));
+        history.record(Operation::Batch(vec![
+            Operation::Delete(lesson(3)),
+            Operation::Delete(lesson(1)),
+        ]));
+
+        // The operations of the batch are renumbered like single operations
+        history.remap(&[0, 2, 4]);
+        assert_eq!(
+            history.pop_undo().unwrap(),
+            Operation::Batch(vec![
+                Operation::Delete(lesson(3)),
+                Operation::Delete(lesson(1))
+            ])
+        );
+        assert_eq!(history.pop_undo().unwrap().get_id(), 4);
+    }
+}
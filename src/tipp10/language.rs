@@ -0,0 +1,190 @@
+/// The training lessons of the English Tipp10 build.
+pub const ENGLISH_LESSON_NAMES: [&str; LESSON_COUNT] = [
+    "Lesson 1 (asdf jkl;)",
+    "Lesson 2 (eo)",
+    "Lesson 3 (ti)",
+    "Lesson 4 (nr)",
+    "Lesson 5 (hc)",
+    "Lesson 6 (Capitalization)",
+    "Lesson 7 (wW.,)",
+    "Lesson 8 (gG)",
+    "Lesson 9 (bBuU)",
+    "Lesson 10 (yYmM)",
+    "Lesson 11 (vVpP)",
+    "Lesson 12 (xX?)",
+    "Lesson 13 (qQ!)",
+    "Lesson 14 (zZ()-)",
+    "Lesson 15 (Special Chars 1)",
+    "Lesson 16 (Numerics)",
+    "Lesson 17 (Special Chars 2)",
+    "Lesson 18 (All Characters)",
+    "Lesson 19 (Numpad 1)",
+    "Lesson 20 (Numpad 2)",
+];
+
+/// The number of training lessons of a Tipp10 build.
+const LESSON_COUNT: usize = 20;
+
+/// Enum to represent the UI language of a Tipp10 build. The language decides the names of the
+/// training lessons. Only the English names are built in; the names of the other languages are
+/// read from the lessons Tipp10 saved.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+}
+impl Language {
+    /// Every supported language.
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+    ];
+
+    /// Get the language from its code or English name. Returns None if the language is not known.
+    pub fn try_from_code(code: &str) -> Option<Self> {
+        match code.trim().to_lowercase().as_str() {
+            "en" | "english" => Some(Language::English),
+            "de" | "german" => Some(Language::German),
+            "fr" | "french" => Some(Language::French),
+            "es" | "spanish" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// Get the code of the language.
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// Get the name of the language in the language itself.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::French => "Français",
+            Language::Spanish => "Español",
+        }
+    }
+
+    /// Get the built-in names of the training lessons ordered by their number. None if the names
+    /// of the language are not built in.
+    pub fn get_lesson_names(&self) -> Option<&'static [&'static str; LESSON_COUNT]> {
+        match self {
+            Language::English => Some(&ENGLISH_LESSON_NAMES),
+            _ => None,
+        }
+    }
+
+    /// Get the word every lesson name starts with.
+    fn get_lesson_word(&self) -> &'static str {
+        match self {
+            Language::English => "Lesson",
+            Language::German => "Lektion",
+            Language::French => "Leçon",
+            Language::Spanish => "Lección",
+        }
+    }
+
+    /// Get the number of a training lesson from its name in this language. Only the word and the
+    /// number at the start of the name are compared, so names that differ in the description of
+    /// the keys are still found.
+    pub fn get_lesson_number(&self, name: &str) -> Option<usize> {
+        let rest = name.trim().strip_prefix(self.get_lesson_word())?;
+        let digits = rest
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>();
+
+        digits
+            .parse::<usize>()
+            .ok()
+            .filter(|number| (1..=LESSON_COUNT).contains(number))
+    }
+
+    /// Get the language that the most lesson names are in. None if no name is in a known language.
+    pub fn detect(names: &[String]) -> Option<Self> {
+        // Reversed so the language that comes first wins a tie
+        Language::ALL
+            .into_iter()
+            .map(|language| {
+                let count = names
+                    .iter()
+                    .filter(|name| language.get_lesson_number(name).is_some())
+                    .count();
+                (language, count)
+            })
+            .filter(|(_, count)| *count > 0)
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(language, _)| language)
+    }
+
+    /// Get the next language to choose. None stands for the language of the database and comes
+    /// after the last language.
+    pub fn next(language: Option<Self>) -> Option<Self> {
+        match language {
+            None => Some(Language::ALL[0]),
+            Some(language) => {
+                let i = Language::ALL.iter().position(|l| *l == language)?;
+                Language::ALL.get(i + 1).copied()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_from_code() {
+        assert_eq!(Language::try_from_code("DE"), Some(Language::German));
+        assert_eq!(Language::try_from_code("spanish"), Some(Language::Spanish));
+        assert_eq!(Language::try_from_code("xx"), None);
+        for language in Language::ALL {
+            assert_eq!(Language::try_from_code(language.get_code()), Some(language));
+        }
+    }
+
+    #[test]
+    fn test_get_lesson_number() {
+        for (i, name) in ENGLISH_LESSON_NAMES.iter().enumerate() {
+            assert_eq!(Language::English.get_lesson_number(name), Some(i + 1));
+        }
+        assert_eq!(Language::German.get_lesson_names(), None);
+        assert_eq!(
+            Language::German.get_lesson_number("Lektion 2 (er)"),
+            Some(2)
+        );
+        assert_eq!(Language::German.get_lesson_number("Lesson 2 (eo)"), None);
+        assert_eq!(Language::English.get_lesson_number("Lesson 21"), None);
+    }
+
+    #[test]
+    fn test_detect() {
+        let names = ["Lektion 1 (asdf jklö)", "Lesson 2 (eo)", "Lektion 18"].map(String::from);
+        assert_eq!(Language::detect(&names), Some(Language::German));
+        assert_eq!(Language::detect(&["Meine Lektion".to_string()]), None);
+    }
+
+    #[test]
+    fn test_next() {
+        let mut language = None;
+        for expected in Language::ALL {
+            language = Language::next(language);
+            assert_eq!(language, Some(expected));
+        }
+        assert_eq!(Language::next(language), None);
+    }
+}
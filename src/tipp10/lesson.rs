@@ -1,27 +1,40 @@
 use std::fmt::Display;
 
+use log::warn;
 use ratatui::{
     style::{Color, Stylize},
     text::Span,
 };
 
-use super::LessonSelection;
+use super::{
+    duration::format_duration, get_datetime_from_timestamp, CatalogueLesson, LessonCatalogue,
+    LessonType,
+};
 
 /// A struct that represents a lesson.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Lesson {
     pub id: usize,
-    pub lesson_id: LessonSelection,
+    /// The number of the lesson in the catalogue. None if the stored lesson is not in the catalogue.
+    pub number: Option<usize>,
+    /// The value of `user_lesson_lesson`. It is written back unchanged.
+    pub user_lesson: String,
+    /// The value of `user_lesson_name`. It is written back unchanged.
+    pub name: String,
+    pub lesson_type: LessonType,
     pub timestamp: u64,
     pub duration: usize,
     pub strokes: usize,
     pub errors: usize,
+    /// The number of characters of the text (`user_lesson_tokenlen`). Unlike the strokes it does
+    /// not count corrections.
+    pub tokens: usize,
 }
 impl Lesson {
     /// Creates a new instance of Lesson.
     pub fn new(
         id: usize,
-        lesson_selection: LessonSelection,
+        catalogue_lesson: &CatalogueLesson,
         timestamp: u64,
         duration: usize,
         strokes: usize,
@@ -29,51 +42,273 @@ impl Lesson {
     ) -> Self {
         Self {
             id,
-            lesson_id: lesson_selection,
+            number: Some(catalogue_lesson.number),
+            user_lesson: catalogue_lesson.user_lesson.clone(),
+            name: catalogue_lesson.name.clone(),
+            lesson_type: LessonType::default(),
             timestamp,
             duration,
             strokes,
             errors,
+            tokens: strokes,
         }
     }
 
+    /// Set the number of characters of the text. It is the number of strokes by default.
+    pub fn with_tokens(mut self, tokens: usize) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Set the type of the lesson.
+    pub fn with_lesson_type(mut self, lesson_type: LessonType) -> Self {
+        self.lesson_type = lesson_type;
+        self
+    }
+
+    /// Set the lesson from the stored lesson number and name. The stored values are kept even if
+    /// the lesson is not in the catalogue.
+    pub fn with_stored_lesson(
+        mut self,
+        user_lesson: &str,
+        name: &str,
+        catalogue: &LessonCatalogue,
+    ) -> Self {
+        self.number = catalogue.find(user_lesson).map(|lesson| lesson.number);
+        if self.number.is_none() {
+            warn!(
+                "Unknown lesson {} ({})! Keeping it unchanged.",
+                name, user_lesson
+            );
+        };
+
+        self.user_lesson = user_lesson.to_string();
+        self.name = name.to_string();
+        self
+    }
+
+    /// Change the lesson to a lesson of the catalogue.
+    pub fn set_catalogue_lesson(&mut self, catalogue_lesson: &CatalogueLesson) {
+        self.number = Some(catalogue_lesson.number);
+        self.user_lesson = catalogue_lesson.user_lesson.clone();
+        self.name = catalogue_lesson.name.clone();
+    }
+
+    /// Get the characters per minute.
+    pub fn get_cpm(&self) -> f64 {
+        if self.duration == 0 {
+            return 0.0;
+        };
+
+        self.strokes as f64 / (self.duration as f64 / 60.0)
+    }
+
+    /// Get the words per minute. A word is five characters.
+    pub fn get_wpm(&self) -> f64 {
+        self.get_cpm() / 5.0
+    }
+
+    /// Get the errors in percent of the strokes.
+    pub fn get_error_rate(&self) -> f64 {
+        if self.strokes == 0 {
+            return 0.0;
+        };
+
+        self.errors as f64 / self.strokes as f64 * 100.0
+    }
+
+    /// Get the rating of the lesson like Tipp10 calculates it. Every error costs 20 strokes.
+    pub fn get_points(&self) -> usize {
+        if self.duration == 0 {
+            return 0;
+        };
+
+        let strokes = self.strokes as f64 - 20.0 * self.errors as f64;
+        let points = strokes / (self.duration as f64 / 60.0) * 0.4;
+
+        points.max(0.0).round() as usize
+    }
+
     /// Get a line of text representing the lesson.
-    pub fn get_line(&self) -> [Span; 19] {
+    pub fn get_line(&self) -> [Span<'static>; 37] {
         [
             Span::from("[ "),
             Span::from("ID: "),
             Span::from(format!("{:<4}", self.id)).fg(Color::Yellow),
             Span::from(" | "),
             Span::from("Name: "),
-            Span::from(format!("{:<29}", self.lesson_id.get_lesson_name())).fg(Color::Yellow),
+            Span::from(format!("{:<29}", self.name)).fg(Color::Yellow),
             Span::from(" | "),
             Span::from("Timestamp: "),
             Span::from(format!("{:<14}", self.timestamp)).fg(Color::Yellow),
             Span::from(" | "),
             Span::from("Duration: "),
-            Span::from(format!("{:<4}", self.duration)).fg(Color::Yellow),
+            Span::from(format!("{:<5}", format_duration(self.duration))).fg(Color::Yellow),
             Span::from(" | "),
             Span::from("Strokes: "),
             Span::from(format!("{:<6}", self.strokes)).fg(Color::Yellow),
             Span::from(" | "),
             Span::from("Errors: "),
             Span::from(format!("{:<6}", self.errors)).fg(Color::Yellow),
+            Span::from(" | "),
+            Span::from("Tokens: "),
+            Span::from(format!("{:<6}", self.tokens)).fg(Color::Yellow),
+            Span::from(" | "),
+            Span::from("Type: "),
+            Span::from(format!("{:<9}", self.lesson_type.get_name())).fg(Color::Yellow),
+            Span::from(" | "),
+            Span::from("CPM: "),
+            Span::from(format!("{:<6.1}", self.get_cpm())).fg(Color::Cyan),
+            Span::from(" | "),
+            Span::from("WPM: "),
+            Span::from(format!("{:<5.1}", self.get_wpm())).fg(Color::Cyan),
+            Span::from(" | "),
+            Span::from("Error rate: "),
+            Span::from(format!("{:<6}", format!("{:.2}%", self.get_error_rate()))).fg(Color::Cyan),
+            Span::from(" | "),
+            Span::from("Points: "),
+            Span::from(format!("{:<4}", self.get_points())).fg(Color::Cyan),
             Span::from(" ]"),
         ]
     }
+
+    /// Get the timestamp in ISO 8601 format. Falls back to the raw timestamp if it is not a valid date.
+    pub fn get_iso_timestamp(&self) -> String {
+        match get_datetime_from_timestamp(self.timestamp) {
+            Some(datetime) => datetime.format("%Y-%m-%dT%H:%M:%S%:z").to_string(),
+            None => self.timestamp.to_string(),
+        }
+    }
+
+    /// Serialize the lesson to a JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"id\":{},\"lesson\":{},\"name\":\"{}\",\"type\":{},\"timestamp\":\"{}\",\"duration\":{},\"strokes\":{},\"errors\":{},\"tokens\":{},\"cpm\":{:.1},\"wpm\":{:.1},\"error_rate\":{:.2},\"points\":{}}}",
+            self.id,
+            self.number
+                .map_or("null".to_string(), |number| number.to_string()),
+            escape_json(&self.name),
+            self.lesson_type.get_type(),
+            escape_json(&self.get_iso_timestamp()),
+            self.duration,
+            self.strokes,
+            self.errors,
+            self.tokens,
+            self.get_cpm(),
+            self.get_wpm(),
+            self.get_error_rate(),
+            self.get_points(),
+        )
+    }
+}
+
+/// Escape a string so it can be used inside a JSON string.
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        };
+    }
+
+    escaped
 }
 
 impl Display for Lesson {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "[ ID: {} | Name: {} | Timestamp: {} | Duration: {} | Strokes: {} | Errors: {} ]",
+            "[ ID: {} | Name: {} | Timestamp: {} | Duration: {} | Strokes: {} | Errors: {} | Tokens: {} | Type: {} | CPM: {:.1} | WPM: {:.1} | Error rate: {:.2}% | Points: {} ]",
             format_args!("{:<4}", self.id),
-            format_args!("{:<29}", self.lesson_id.get_lesson_name()),
+            format_args!("{:<29}", self.name),
             self.timestamp,
-            format_args!("{:<4}", self.duration),
+            format_args!("{:<5}", format_duration(self.duration)),
             format_args!("{:<6}", self.strokes),
             format_args!("{:<6}", self.errors),
+            format_args!("{:<6}", self.tokens),
+            format_args!("{:<9}", self.lesson_type.get_name()),
+            self.get_cpm(),
+            self.get_wpm(),
+            self.get_error_rate(),
+            self.get_points(),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = Lesson::new(3, catalogue.get(2).unwrap(), 20230101120000, 600, 1200, 12)
+            .with_lesson_type(LessonType::Own)
+            .with_tokens(1150);
+        let json = lesson.to_json();
+        assert!(json.starts_with(
+            "{\"id\":3,\"lesson\":2,\"name\":\"Lesson 2 (eo)\",\"type\":2,\"timestamp\":\"2023-01-01T12:00:00"
+        ));
+        assert!(json.ends_with(
+            "\"duration\":600,\"strokes\":1200,\"errors\":12,\"tokens\":1150,\"cpm\":120.0,\"wpm\":24.0,\"error_rate\":1.00,\"points\":38}"
+        ));
+
+        let unknown = lesson.with_stored_lesson("302", "Own lesson", &catalogue);
+        assert!(unknown
+            .to_json()
+            .contains("\"lesson\":null,\"name\":\"Own lesson\""));
+    }
+
+    #[test]
+    fn test_with_stored_lesson() {
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = Lesson::new(0, catalogue.get(1).unwrap(), 20230101120000, 600, 1200, 12);
+
+        let known = lesson
+            .clone()
+            .with_stored_lesson("102", "Lesson 2 (eo)", &catalogue);
+        assert_eq!(known.number, Some(2));
+
+        // A lesson of a database in another language keeps its name
+        let translated = lesson
+            .clone()
+            .with_stored_lesson("102", "Lektion 2 (er)", &catalogue);
+        assert_eq!(translated.number, Some(2));
+        assert_eq!(translated.name, "Lektion 2 (er)");
+
+        let unknown = lesson.with_stored_lesson("302", "Own lesson", &catalogue);
+        assert_eq!(unknown.number, None);
+        assert_eq!(unknown.user_lesson, "302");
+    }
+
+    #[test]
+    fn test_metrics() {
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = Lesson::new(0, catalogue.get(1).unwrap(), 20230101120000, 300, 1000, 10);
+        assert_eq!(lesson.get_cpm(), 200.0);
+        assert_eq!(lesson.get_wpm(), 40.0);
+        assert_eq!(lesson.get_error_rate(), 1.0);
+        // (1000 - 20 * 10) / 5 minutes * 0.4
+        assert_eq!(lesson.get_points(), 64);
+
+        // Empty lessons and lessons with many errors do not divide by zero or go negative
+        let empty = Lesson::new(0, catalogue.get(1).unwrap(), 20230101120000, 0, 0, 0);
+        assert_eq!(empty.get_cpm(), 0.0);
+        assert_eq!(empty.get_error_rate(), 0.0);
+        assert_eq!(empty.get_points(), 0);
+        let sloppy = Lesson::new(0, catalogue.get(1).unwrap(), 20230101120000, 60, 100, 10);
+        assert_eq!(sloppy.get_points(), 0);
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+        assert_eq!(escape_json("\u{1}"), "\\u0001");
+    }
+}
@@ -0,0 +1,84 @@
+use log::warn;
+
+/// Enum to represent the kind of a lesson as Tipp10 stores it in `user_lesson_type`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum LessonType {
+    /// One of the training lessons
+    #[default]
+    Training,
+    /// An open text lesson
+    OpenText,
+    /// A lesson created by the user
+    Own,
+    /// A type that is not known. It is written back unchanged.
+    Unknown(i64),
+}
+impl LessonType {
+    /// Get the lesson type from the value of `user_lesson_type`
+    pub fn from_type(n: i64) -> Self {
+        match n {
+            0 => LessonType::Training,
+            1 => LessonType::OpenText,
+            2 => LessonType::Own,
+            _ => {
+                warn!("Unknown lesson type {}! Keeping it unchanged.", n);
+                LessonType::Unknown(n)
+            }
+        }
+    }
+
+    /// Get the lesson type from its number or name. Returns None if neither is known.
+    pub fn try_from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "0" | "training" => Some(LessonType::Training),
+            "1" | "open-text" | "open text" | "opentext" => Some(LessonType::OpenText),
+            "2" | "own" => Some(LessonType::Own),
+            _ => None,
+        }
+    }
+
+    /// Get the value that is stored in `user_lesson_type`
+    pub fn get_type(&self) -> i64 {
+        match self {
+            LessonType::Training => 0,
+            LessonType::OpenText => 1,
+            LessonType::Own => 2,
+            LessonType::Unknown(n) => *n,
+        }
+    }
+
+    /// Get the name of the lesson type
+    pub fn get_name(&self) -> String {
+        match self {
+            LessonType::Training => "Training".to_string(),
+            LessonType::OpenText => "Open text".to_string(),
+            LessonType::Own => "Own".to_string(),
+            LessonType::Unknown(n) => format!("Type {}", n),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_type() {
+        for n in 0..4 {
+            assert_eq!(LessonType::from_type(n).get_type(), n);
+        }
+        assert_eq!(LessonType::from_type(1), LessonType::OpenText);
+        assert_eq!(LessonType::from_type(7), LessonType::Unknown(7));
+    }
+
+    #[test]
+    fn test_try_from_name() {
+        assert_eq!(LessonType::try_from_name("own"), Some(LessonType::Own));
+        assert_eq!(
+            LessonType::try_from_name("Open text"),
+            Some(LessonType::OpenText)
+        );
+        assert_eq!(LessonType::try_from_name("0"), Some(LessonType::Training));
+        assert_eq!(LessonType::try_from_name("3"), None);
+    }
+}
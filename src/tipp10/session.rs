@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use log::{info, trace, warn};
+use rusqlite::Connection;
+
+use super::{backup, validation::ValidationRules, History, IdMode, Language, SQLiteError};
+
+/// The default number of backups that are kept.
+pub const DEFAULT_BACKUP_COUNT: usize = 5;
+
+/// Settings and state shared by every write of one run of the application.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub id_mode: IdMode,
+    /// Number of backups to keep. No backup is made if 0.
+    pub backup_count: usize,
+    backed_up: bool,
+    /// The writes of the session that can be undone.
+    pub history: History,
+    /// The language of the lesson names of new or changed lessons. None to use the lessons of the
+    /// database.
+    pub language: Option<Language>,
+    /// The rules new or changed lessons are checked with.
+    pub validation_rules: ValidationRules,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new(IdMode::default(), DEFAULT_BACKUP_COUNT)
+    }
+}
+
+impl Session {
+    /// Creates a new instance of Session.
+    pub fn new(id_mode: IdMode, backup_count: usize) -> Self {
+        Self {
+            id_mode,
+            backup_count,
+            backed_up: false,
+            history: History::default(),
+            language: None,
+            validation_rules: ValidationRules::default(),
+        }
+    }
+
+    /// Must be called before every write. Backs up the database before the first write of the session.
+    pub fn before_write(&mut self, conn: &Connection) -> Result<(), SQLiteError> {
+        self.back_up_once(conn, None)
+    }
+
+    /// Must be called before a backup is restored. Like `before_write`, but the backup to restore
+    /// is never removed when the old backups are rotated.
+    pub fn before_restore(&mut self, conn: &Connection, path: &Path) -> Result<(), SQLiteError> {
+        self.back_up_once(conn, Some(path))
+    }
+
+    /// Back up the database if this is the first write of the session.
+    fn back_up_once(
+        &mut self,
+        conn: &Connection,
+        protected: Option<&Path>,
+    ) -> Result<(), SQLiteError> {
+        if self.backed_up || self.backup_count == 0 {
+            return Ok(());
+        };
+
+        // In-memory databases have no file that could be backed up
+        match conn.path() {
+            Some(path) if !path.is_empty() => {
+                let backup_path = backup::create_backup(conn, self.backup_count, protected)?;
+                info!("Database backed up to {}!", backup_path.display());
+            }
+            _ => warn!("Database has no file! Skipping backup."),
+        };
+
+        trace!("First write of the session!");
+        self.backed_up = true;
+        Ok(())
+    }
+}
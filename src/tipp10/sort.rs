@@ -0,0 +1,171 @@
+use std::cmp::Ordering;
+
+use super::Lesson;
+
+/// The column the lessons are sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SortColumn {
+    #[default]
+    Id,
+    Lesson,
+    Name,
+    Timestamp,
+    Duration,
+    Strokes,
+    Errors,
+    Tokens,
+    Type,
+    Cpm,
+    Wpm,
+    ErrorRate,
+    Points,
+}
+impl SortColumn {
+    /// Every column in the order they are shown.
+    pub const ALL: [SortColumn; 13] = [
+        SortColumn::Id,
+        SortColumn::Lesson,
+        SortColumn::Name,
+        SortColumn::Timestamp,
+        SortColumn::Duration,
+        SortColumn::Strokes,
+        SortColumn::Errors,
+        SortColumn::Tokens,
+        SortColumn::Type,
+        SortColumn::Cpm,
+        SortColumn::Wpm,
+        SortColumn::ErrorRate,
+        SortColumn::Points,
+    ];
+
+    /// Get the name of the column.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SortColumn::Id => "ID",
+            SortColumn::Lesson => "Lesson",
+            SortColumn::Name => "Name",
+            SortColumn::Timestamp => "Timestamp",
+            SortColumn::Duration => "Duration",
+            SortColumn::Strokes => "Strokes",
+            SortColumn::Errors => "Errors",
+            SortColumn::Tokens => "Tokens",
+            SortColumn::Type => "Type",
+            SortColumn::Cpm => "CPM",
+            SortColumn::Wpm => "WPM",
+            SortColumn::ErrorRate => "Error rate",
+            SortColumn::Points => "Points",
+        }
+    }
+
+    /// Get the next column. The first column follows the last one.
+    pub fn next(&self) -> Self {
+        let i = SortColumn::ALL.iter().position(|column| column == self);
+        SortColumn::ALL[i.map_or(0, |i| (i + 1) % SortColumn::ALL.len())]
+    }
+
+    /// Get the previous column. The last column follows the first one.
+    pub fn previous(&self) -> Self {
+        let i = SortColumn::ALL.iter().position(|column| column == self);
+        SortColumn::ALL[i.map_or(0, |i| {
+            (i + SortColumn::ALL.len() - 1) % SortColumn::ALL.len()
+        })]
+    }
+
+    /// Compare two lessons by the value of the column in ascending order.
+    pub fn compare(&self, a: &Lesson, b: &Lesson) -> Ordering {
+        match self {
+            SortColumn::Id => a.id.cmp(&b.id),
+            SortColumn::Lesson => a.number.cmp(&b.number),
+            SortColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            SortColumn::Timestamp => a.timestamp.cmp(&b.timestamp),
+            SortColumn::Duration => a.duration.cmp(&b.duration),
+            SortColumn::Strokes => a.strokes.cmp(&b.strokes),
+            SortColumn::Errors => a.errors.cmp(&b.errors),
+            SortColumn::Tokens => a.tokens.cmp(&b.tokens),
+            SortColumn::Type => a.lesson_type.get_type().cmp(&b.lesson_type.get_type()),
+            SortColumn::Cpm => a.get_cpm().total_cmp(&b.get_cpm()),
+            SortColumn::Wpm => a.get_wpm().total_cmp(&b.get_wpm()),
+            SortColumn::ErrorRate => a.get_error_rate().total_cmp(&b.get_error_rate()),
+            SortColumn::Points => a.get_points().cmp(&b.get_points()),
+        }
+    }
+}
+
+/// The order of the lessons in the lesson list.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LessonSort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+impl LessonSort {
+    /// Compare two lessons. Lessons with the same value are ordered by their ID, so the order
+    /// does not change between updates.
+    pub fn compare(&self, a: &Lesson, b: &Lesson) -> Ordering {
+        let ordering = self.column.compare(a, b).then_with(|| a.id.cmp(&b.id));
+
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    /// Get the symbol that shows the direction of the order.
+    pub fn get_symbol(&self) -> &'static str {
+        if self.descending {
+            "▼"
+        } else {
+            "▲"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::new_lesson;
+
+    use super::*;
+
+    #[test]
+    fn test_sort_column() {
+        assert_eq!(SortColumn::Id.previous(), SortColumn::Points);
+        assert_eq!(SortColumn::Points.next(), SortColumn::Id);
+        assert_eq!(SortColumn::Name.next(), SortColumn::Timestamp);
+    }
+
+    #[test]
+    fn test_compare() {
+        let mut lessons = [
+            new_lesson(2, 300, 3, 60, 20240301120000),
+            new_lesson(1, 100, 0, 60, 20240302120000),
+            new_lesson(3, 200, 9, 60, 20240301120000),
+        ];
+        for (i, lesson) in lessons.iter_mut().enumerate() {
+            lesson.id = i + 1;
+        }
+        let ids = |sort: LessonSort, lessons: &mut [Lesson]| {
+            lessons.sort_by(|a, b| sort.compare(a, b));
+            lessons
+                .iter()
+                .map(|lesson| lesson.id)
+                .collect::<Vec<usize>>()
+        };
+
+        let mut sort = LessonSort {
+            column: SortColumn::Cpm,
+            descending: false,
+        };
+        assert_eq!(ids(sort, &mut lessons), vec![2, 3, 1]);
+
+        sort.column = SortColumn::ErrorRate;
+        sort.descending = true;
+        assert_eq!(ids(sort, &mut lessons), vec![3, 1, 2]);
+
+        // Ties are ordered by the ID
+        sort.column = SortColumn::Timestamp;
+        sort.descending = false;
+        assert_eq!(ids(sort, &mut lessons), vec![1, 3, 2]);
+        sort.descending = true;
+        assert_eq!(ids(sort, &mut lessons), vec![2, 3, 1]);
+    }
+}
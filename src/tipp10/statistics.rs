@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, Months, TimeDelta};
+
+use super::{get_datetime_from_timestamp, get_timestamp_from_now, Lesson};
+
+/// The time span of the lessons that are shown in the statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DateRange {
+    Week,
+    Month,
+    Year,
+    #[default]
+    All,
+}
+impl DateRange {
+    /// Every date range from the shortest to the longest.
+    pub const ALL: [DateRange; 4] = [
+        DateRange::Week,
+        DateRange::Month,
+        DateRange::Year,
+        DateRange::All,
+    ];
+
+    /// Get the name of the date range.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            DateRange::Week => "Last 7 days",
+            DateRange::Month => "Last month",
+            DateRange::Year => "Last year",
+            DateRange::All => "All time",
+        }
+    }
+
+    /// Get the next longer date range. The longest one stays selected.
+    pub fn next(&self) -> Self {
+        let i = DateRange::ALL.iter().position(|range| range == self);
+        DateRange::ALL[i.map_or(0, |i| (i + 1).min(DateRange::ALL.len() - 1))]
+    }
+
+    /// Get the next shorter date range. The shortest one stays selected.
+    pub fn previous(&self) -> Self {
+        let i = DateRange::ALL.iter().position(|range| range == self);
+        DateRange::ALL[i.map_or(0, |i| i.saturating_sub(1))]
+    }
+
+    /// Get the earliest tipp10 timestamp of the date range. None if the range has no start.
+    pub fn get_start(&self, now: &DateTime<Local>) -> Option<u64> {
+        let start = match self {
+            DateRange::Week => now.checked_sub_signed(TimeDelta::days(7)),
+            DateRange::Month => now.checked_sub_months(Months::new(1)),
+            DateRange::Year => now.checked_sub_months(Months::new(12)),
+            DateRange::All => None,
+        }?;
+
+        Some(get_timestamp_from_now(&start))
+    }
+
+    /// Get the lessons that were done in the date range, ordered by their timestamp.
+    pub fn filter<'a>(&self, lessons: &'a [Lesson], now: &DateTime<Local>) -> Vec<&'a Lesson> {
+        let start = self.get_start(now);
+
+        let mut lessons = lessons
+            .iter()
+            .filter(|lesson| start.is_none_or(|start| lesson.timestamp >= start))
+            .collect::<Vec<&Lesson>>();
+        lessons.sort_by_key(|lesson| lesson.timestamp);
+
+        lessons
+    }
+}
+
+/// The averages of the lessons with the same lesson number.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LessonStatistics {
+    /// The number of the lesson. None for lessons that are not in the catalogue.
+    pub number: Option<usize>,
+    /// The number of lessons that were done.
+    pub count: usize,
+    pub average_cpm: f64,
+    pub average_error_rate: f64,
+}
+
+/// Get the averages of the lessons grouped by their lesson number. Lessons that are not in the
+/// catalogue come last.
+pub fn get_lesson_statistics(lessons: &[&Lesson]) -> Vec<LessonStatistics> {
+    let mut groups: BTreeMap<Option<usize>, Vec<&Lesson>> = BTreeMap::new();
+    for lesson in lessons {
+        groups.entry(lesson.number).or_default().push(lesson);
+    }
+
+    let mut statistics = groups
+        .into_iter()
+        .map(|(number, lessons)| LessonStatistics {
+            number,
+            count: lessons.len(),
+            average_cpm: get_average(&lessons, Lesson::get_cpm),
+            average_error_rate: get_average(&lessons, Lesson::get_error_rate),
+        })
+        .collect::<Vec<LessonStatistics>>();
+
+    // None is ordered before every number
+    let unknown = statistics
+        .iter()
+        .take_while(|statistics| statistics.number.is_none())
+        .count();
+    statistics.rotate_left(unknown);
+
+    statistics
+}
+
+/// Get the average of a metric of the lessons. 0 if there are no lessons.
+pub fn get_average(lessons: &[&Lesson], metric: fn(&Lesson) -> f64) -> f64 {
+    if lessons.is_empty() {
+        return 0.0;
+    };
+
+    lessons.iter().map(|lesson| metric(lesson)).sum::<f64>() / lessons.len() as f64
+}
+
+/// Get the points of a metric over time. The x value is the Unix timestamp of the lesson. Lessons
+/// with an invalid timestamp are left out.
+pub fn get_time_series(lessons: &[&Lesson], metric: fn(&Lesson) -> f64) -> Vec<(f64, f64)> {
+    lessons
+        .iter()
+        .filter_map(|lesson| {
+            let datetime = get_datetime_from_timestamp(lesson.timestamp)?;
+            Some((datetime.timestamp() as f64, metric(lesson)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::tipp10::tests::new_lesson;
+
+    use super::*;
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_date_range() {
+        assert_eq!(DateRange::Week.previous(), DateRange::Week);
+        assert_eq!(DateRange::Week.next(), DateRange::Month);
+        assert_eq!(DateRange::All.next(), DateRange::All);
+        assert_eq!(DateRange::Month.get_start(&now()), Some(20240215120000));
+        assert_eq!(DateRange::All.get_start(&now()), None);
+
+        let lessons = [
+            new_lesson(1, 100, 1, 60, 20240314120000),
+            new_lesson(1, 100, 1, 60, 20230101120000),
+            new_lesson(1, 100, 1, 60, 20240301120000),
+        ];
+        let week = DateRange::Week.filter(&lessons, &now());
+        assert_eq!(week.len(), 1);
+        let all = DateRange::All.filter(&lessons, &now());
+        assert_eq!(all[0].timestamp, 20230101120000);
+        assert_eq!(all[2].timestamp, 20240314120000);
+    }
+
+    #[test]
+    fn test_lesson_statistics() {
+        let mut unknown = new_lesson(1, 100, 0, 60, 20240301120000);
+        unknown.number = None;
+        let lessons = [
+            new_lesson(2, 200, 2, 60, 20240301120000),
+            new_lesson(1, 100, 0, 60, 20240301120000),
+            new_lesson(2, 100, 2, 60, 20240301120000),
+            unknown,
+        ];
+        let lessons = lessons.iter().collect::<Vec<&Lesson>>();
+
+        let statistics = get_lesson_statistics(&lessons);
+        assert_eq!(
+            statistics
+                .iter()
+                .map(|statistics| statistics.number)
+                .collect::<Vec<Option<usize>>>(),
+            vec![Some(1), Some(2), None]
+        );
+        assert_eq!(statistics[1].count, 2);
+        assert_eq!(statistics[1].average_cpm, 150.0);
+        assert_eq!(statistics[1].average_error_rate, 1.5);
+    }
+
+    #[test]
+    fn test_time_series() {
+        let mut invalid = new_lesson(1, 100, 0, 60, 20240301120000);
+        invalid.timestamp = 0;
+        let lessons = [new_lesson(1, 100, 0, 60, 20240301120000), invalid];
+        let lessons = lessons.iter().collect::<Vec<&Lesson>>();
+
+        let series = get_time_series(&lessons, Lesson::get_cpm);
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].1, 100.0);
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt::Display;
+
+use log::warn;
+
+use super::{
+    duration::format_duration, get_naive_datetime_from_tipp10_str, get_timestamp, Lesson,
+    SQLiteError,
+};
+
+/// How serious a problem of a lesson is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    /// The lesson is unlikely but can be saved.
+    Warning,
+    /// The lesson can not be saved.
+    Error,
+}
+
+/// The value of a lesson a problem is about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueField {
+    Timestamp,
+    Duration,
+    Errors,
+    /// The characters per minute of the strokes and the duration.
+    Cpm,
+    /// The error rate of the errors and the strokes.
+    ErrorRate,
+}
+
+/// A problem of a lesson found by the validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The value that causes the problem.
+    pub field: IssueField,
+    pub message: String,
+}
+impl Issue {
+    fn new(severity: Severity, field: IssueField, message: String) -> Self {
+        Self {
+            severity,
+            field,
+            message,
+        }
+    }
+}
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "Warning: {}", self.message),
+            Severity::Error => write!(f, "Error: {}", self.message),
+        }
+    }
+}
+
+/// The limits of a plausible lesson. Lessons beyond them get a warning.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationRules {
+    /// The highest plausible characters per minute.
+    pub max_cpm: f64,
+    /// The longest plausible duration in seconds.
+    pub max_duration: usize,
+    /// The highest plausible error rate in percent.
+    pub max_error_rate: f64,
+    /// Accept timestamps in the future instead of rejecting them.
+    pub allow_future: bool,
+}
+
+impl Default for ValidationRules {
+    fn default() -> Self {
+        Self {
+            max_cpm: 1000.0,
+            max_duration: 4 * 3600,
+            max_error_rate: 50.0,
+            allow_future: false,
+        }
+    }
+}
+
+/// Check the lesson against the rules. `now` is the tipp10 timestamp timestamps in the future are
+/// compared with.
+///
+/// Errors are lessons that can not have happened: more errors than strokes, strokes without any
+/// time, timestamps that are not a date or in the future. Everything beyond the limits of the
+/// rules is a warning.
+pub fn validate_lesson(lesson: &Lesson, rules: &ValidationRules, now: u64) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    if lesson.errors > lesson.strokes {
+        issues.push(Issue::new(
+            Severity::Error,
+            IssueField::Errors,
+            format!(
+                "{} errors are more than the {} strokes",
+                lesson.errors, lesson.strokes
+            ),
+        ));
+    };
+    if lesson.duration == 0 && lesson.strokes > 0 {
+        issues.push(Issue::new(
+            Severity::Error,
+            IssueField::Duration,
+            format!("{} strokes in no time", lesson.strokes),
+        ));
+    };
+    if get_naive_datetime_from_tipp10_str(&lesson.timestamp.to_string()).is_none() {
+        issues.push(Issue::new(
+            Severity::Error,
+            IssueField::Timestamp,
+            format!("{} is not a valid date", lesson.timestamp),
+        ));
+    } else if lesson.timestamp > now && !rules.allow_future {
+        issues.push(Issue::new(
+            Severity::Error,
+            IssueField::Timestamp,
+            "The timestamp is in the future".to_string(),
+        ));
+    };
+
+    if lesson.duration > 0 && lesson.get_cpm() > rules.max_cpm {
+        issues.push(Issue::new(
+            Severity::Warning,
+            IssueField::Cpm,
+            format!(
+                "{:.0} characters per minute are more than {:.0}",
+                lesson.get_cpm(),
+                rules.max_cpm
+            ),
+        ));
+    };
+    if lesson.duration > rules.max_duration {
+        issues.push(Issue::new(
+            Severity::Warning,
+            IssueField::Duration,
+            format!("Longer than {}", format_duration(rules.max_duration)),
+        ));
+    };
+    if lesson.errors <= lesson.strokes && lesson.get_error_rate() > rules.max_error_rate {
+        issues.push(Issue::new(
+            Severity::Warning,
+            IssueField::ErrorRate,
+            format!(
+                "An error rate of {:.1}% is above {}%",
+                lesson.get_error_rate(),
+                rules.max_error_rate
+            ),
+        ));
+    };
+
+    issues
+}
+
+/// Check the lesson before it is written. Returns the warnings or an error with every problem
+/// that prevents writing the lesson.
+pub fn check_lesson(lesson: &Lesson, rules: &ValidationRules) -> Result<Vec<Issue>, SQLiteError> {
+    let (errors, warnings) = validate_lesson(lesson, rules, get_timestamp())
+        .into_iter()
+        .partition::<Vec<Issue>, _>(|issue| issue.severity == Severity::Error);
+
+    if !errors.is_empty() {
+        return Err(SQLiteError::InvalidLesson(errors));
+    };
+    for issue in &warnings {
+        warn!("Implausible lesson with ID {}! {}", lesson.id, issue);
+    }
+
+    Ok(warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tipp10::tests::new_lesson;
+
+    use super::*;
+
+    fn get_issues(lesson: &Lesson, rules: &ValidationRules) -> Vec<(Severity, IssueField)> {
+        validate_lesson(lesson, rules, 20240301120000)
+            .iter()
+            .map(|issue| (issue.severity, issue.field))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_lesson() {
+        let mut rules = ValidationRules::default();
+
+        assert!(get_issues(&new_lesson(1, 1200, 12, 600, 20240301115000), &rules).is_empty());
+        assert_eq!(
+            get_issues(&new_lesson(1, 10, 20, 600, 20240301115000), &rules),
+            vec![(Severity::Error, IssueField::Errors)]
+        );
+        assert_eq!(
+            get_issues(&new_lesson(1, 5000, 0, 0, 20240301115000), &rules),
+            vec![(Severity::Error, IssueField::Duration)]
+        );
+        assert_eq!(
+            get_issues(&new_lesson(1, 100, 0, 60, 20240230120000), &rules),
+            vec![(Severity::Error, IssueField::Timestamp)]
+        );
+        assert_eq!(
+            get_issues(&new_lesson(1, 100, 60, 60, 20240302120000), &rules),
+            vec![
+                (Severity::Error, IssueField::Timestamp),
+                (Severity::Warning, IssueField::ErrorRate)
+            ]
+        );
+
+        // The limits of the warnings can be changed
+        let lesson = new_lesson(1, 3000, 0, 60, 20240301115000);
+        assert_eq!(
+            get_issues(&lesson, &rules),
+            vec![(Severity::Warning, IssueField::Cpm)]
+        );
+        rules.max_cpm = 5000.0;
+        rules.allow_future = true;
+        assert!(get_issues(&lesson, &rules).is_empty());
+        assert!(get_issues(&new_lesson(1, 100, 0, 60, 20250101120000), &rules).is_empty());
+    }
+
+    #[test]
+    fn test_check_lesson() {
+        let rules = ValidationRules::default();
+        let lesson = new_lesson(1, 100, 0, 5 * 3600, 20240301120000);
+        assert_eq!(check_lesson(&lesson, &rules).unwrap().len(), 1);
+
+        let lesson = new_lesson(1, 10, 20, 600, 20240301120000);
+        assert!(matches!(
+            check_lesson(&lesson, &rules),
+            Err(SQLiteError::InvalidLesson(errors)) if errors.len() == 1
+        ));
+    }
+}
@@ -6,6 +6,7 @@ use crate::state::{State, SubState};
 #[derive(Debug, PartialEq)]
 pub enum EventResult {
     Submit,
+    Cancel,
     SetState(State),
     SetSubState(SubState),
     None(ResultError),
@@ -19,11 +20,19 @@ pub enum ResultError {
     SQLite,
     Io,
     NoLessons,
+    NoBackups,
+    NothingToUndo,
+    NothingToRedo,
     MaxLenReached,
     Timestamp,
     WrongInput,
     OutOfBounds,
     TextBoxError,
+    LessonsChanged(usize),
+    /// A lesson that can not be saved with the message why.
+    Invalid(String),
+    /// A lesson that was saved but is unlikely with the message why.
+    Warning(String),
 }
 impl Display for ResultError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -32,11 +41,17 @@ impl Display for ResultError {
             ResultError::SQLite => write!(f, "SQLite error occurred!"),
             ResultError::Io => write!(f, "Io error occurred!"),
             ResultError::NoLessons => write!(f, "No lessons found!"),
+            ResultError::NoBackups => write!(f, "No backups found!"),
+            ResultError::NothingToUndo => write!(f, "Nothing to undo!"),
+            ResultError::NothingToRedo => write!(f, "Nothing to redo!"),
             ResultError::MaxLenReached => write!(f, "Max length reached!"),
             ResultError::Timestamp => write!(f, "Timestamp error occurred!"),
             ResultError::WrongInput => write!(f, "Wrong input!"),
             ResultError::OutOfBounds => write!(f, "Out of bounds error occurred!"),
             ResultError::TextBoxError => write!(f, "TextBox error occurred!"),
+            ResultError::LessonsChanged(count) => write!(f, "{} lessons changed!", count),
+            ResultError::Invalid(message) => write!(f, "{}", message),
+            ResultError::Warning(message) => write!(f, "Warning: {}", message),
         }
     }
 }
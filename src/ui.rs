@@ -1,14 +1,17 @@
 use std::io;
 
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Stylize},
     text::{Line, Span},
     widgets::{Block, BorderType, Borders},
-    DefaultTerminal,
+    DefaultTerminal, Frame,
 };
 
-use crate::{state::State, tipp10w::Tipp10W};
+use crate::{
+    state::{State, SubState},
+    tipp10w::Tipp10W,
+};
 
 impl Tipp10W {
     pub fn draw_ui(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
@@ -53,15 +56,6 @@ impl Tipp10W {
                         .direction(Direction::Vertical)
                         .constraints([Constraint::Min(3), Constraint::Length(1)])
                         .split(f.area());
-                    // Create a horizontal layout with 3 chunks
-                    let status_bar = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints([
-                            Constraint::Fill(4),
-                            Constraint::Length(1),
-                            Constraint::Fill(2),
-                        ])
-                        .split(chunks_vertical[1]);
 
                     // Render the lessons widget in the top chunk of the vertical layout
                     self.app_state.lessons_widget.render(
@@ -72,45 +66,247 @@ impl Tipp10W {
                     );
 
                     // Create the help line
-                    let help = Line::from_iter([
-                        Span::from("Exit: "),
-                        Span::from("Esc").fg(Color::Yellow),
-                        Span::from(" | Up").fg(Color::Yellow),
-                        Span::from(" | "),
-                        Span::from("Down").fg(Color::Yellow),
-                        Span::from(" | "),
-                        Span::from("n").fg(Color::Yellow),
-                        Span::from(": new | "),
-                        Span::from("Enter").fg(Color::Yellow),
-                        Span::from(": Edit | "),
-                        Span::from("Del").fg(Color::Yellow),
-                        Span::from(": Delete | "),
-                        Span::from("u").fg(Color::Yellow),
-                        Span::from(": update | "),
-                        Span::from("Date: "),
-                        Span::from("YYYYMMDDHHmmss").fg(Color::Yellow),
-                    ])
-                    .alignment(Alignment::Center);
-
-                    // Render the help line in the left chunk of the status bar
-                    f.render_widget(help, status_bar[0]);
-
-                    // Render a vertical separator in the middle chunk of the status bar
-                    f.render_widget(Line::from("┃"), status_bar[1]);
-
-                    // Render the error message in the right chunk of the status bar
-                    f.render_widget(
+                    let bulk_example = self.app_state.lessons_widget.get_bulk_example();
+                    let is_choosing_lesson =
+                        self.app_state.lessons_widget.is_choosing_bulk_lesson();
+                    let mut help = if let (SubState::Bulk, true) = (&*sub_state, is_choosing_lesson)
+                    {
                         Line::from_iter([
-                            Span::from(" "),
-                            Span::from(self.app_state.error.as_str()).fg(Color::Red),
+                            Span::from("Choose: "),
+                            Span::from("Up").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Down").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("PgUp").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("PgDn").fg(Color::Yellow),
+                            Span::from(" | Filter: type a number or name | Apply: "),
+                            Span::from("Enter").fg(Color::Yellow),
+                            Span::from(" | Cancel: "),
+                            Span::from("Esc").fg(Color::Yellow),
                         ])
-                        .alignment(Alignment::Center),
-                        status_bar[2],
-                    );
+                    } else if let (SubState::Bulk, Some(example)) = (&*sub_state, bulk_example) {
+                        Line::from_iter([
+                            Span::from("Apply: "),
+                            Span::from("Enter").fg(Color::Yellow),
+                            Span::from(" | Cancel: "),
+                            Span::from("Esc").fg(Color::Yellow),
+                            Span::from(" | Examples: "),
+                            Span::from(example).fg(Color::Yellow),
+                        ])
+                    } else if let SubState::Edit(_) = sub_state {
+                        Line::from_iter([
+                            Span::from("Next: "),
+                            Span::from("Tab").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Down").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Enter").fg(Color::Yellow),
+                            Span::from(" | Previous: "),
+                            Span::from("Shift+Tab").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Up").fg(Color::Yellow),
+                            Span::from(" | Calendar: "),
+                            Span::from("Ctrl+D").fg(Color::Yellow),
+                            Span::from(" | Save: "),
+                            Span::from("Ctrl+S").fg(Color::Yellow),
+                            Span::from(" | Cancel: "),
+                            Span::from("Esc").fg(Color::Yellow),
+                        ])
+                    } else if let SubState::Search = sub_state {
+                        Line::from_iter([
+                            Span::from("Keep filter: "),
+                            Span::from("Enter").fg(Color::Yellow),
+                            Span::from(" | Clear: "),
+                            Span::from("Esc").fg(Color::Yellow),
+                            Span::from(" | Name, "),
+                            Span::from("2024-03").fg(Color::Yellow),
+                            Span::from(", "),
+                            Span::from("2024-01..2024-03").fg(Color::Yellow),
+                            Span::from(", "),
+                            Span::from("errors>50").fg(Color::Yellow),
+                            Span::from(", "),
+                            Span::from("cpm>=200").fg(Color::Yellow),
+                        ])
+                    } else {
+                        Line::from_iter([
+                            Span::from("Exit: "),
+                            Span::from("Esc").fg(Color::Yellow),
+                            Span::from(" | Up").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("Down").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("PgUp").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("PgDn").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("Home").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("End").fg(Color::Yellow),
+                            Span::from(" | "),
+                            Span::from("n").fg(Color::Yellow),
+                            Span::from(": new | "),
+                            Span::from("Left").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Right").fg(Color::Yellow),
+                            Span::from(": sort | "),
+                            Span::from("r").fg(Color::Yellow),
+                            Span::from(": reverse | "),
+                            Span::from("Enter").fg(Color::Yellow),
+                            Span::from(": Edit | "),
+                            Span::from("Del").fg(Color::Yellow),
+                            Span::from(": Delete | "),
+                            Span::from("Space").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("Shift+Up/Down").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("a").fg(Color::Yellow),
+                            Span::from(": mark | "),
+                            Span::from("#").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("t").fg(Color::Yellow),
+                            Span::from(" / "),
+                            Span::from("d").fg(Color::Yellow),
+                            Span::from(": set lesson / shift time / scale duration | "),
+                            Span::from("u").fg(Color::Yellow),
+                            Span::from(": update | "),
+                            Span::from("z").fg(Color::Yellow),
+                            Span::from(": undo | "),
+                            Span::from("y").fg(Color::Yellow),
+                            Span::from(": redo | "),
+                            Span::from("l").fg(Color::Yellow),
+                            Span::from(": language | "),
+                            Span::from("b").fg(Color::Yellow),
+                            Span::from(": backups | "),
+                            Span::from("s").fg(Color::Yellow),
+                            Span::from(": statistics | "),
+                            Span::from("c").fg(Color::Yellow),
+                            Span::from(": calendar | "),
+                            Span::from("/").fg(Color::Yellow),
+                            Span::from(": search"),
+                        ])
+                    };
+
+                    // Show how many lessons match the filter
+                    if let Some((matches, count)) = self.app_state.lessons_widget.get_match_count()
+                    {
+                        help.spans.insert(
+                            0,
+                            Span::from(format!("{} of {} lessons | ", matches, count))
+                                .fg(Color::Cyan),
+                        );
+                    };
+
+                    render_status_bar(f, chunks_vertical[1], help, &self.app_state.error);
                 }
             }
+            State::Backups => {
+                // Create a vertical layout with 2 chunks
+                let chunks_vertical = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(3), Constraint::Length(1)])
+                    .split(f.area());
+
+                // Render the backups widget in the top chunk of the vertical layout
+                self.app_state.backups_widget.render(f, chunks_vertical[0]);
+
+                // Create the help line
+                let help = Line::from_iter([
+                    Span::from("Back: "),
+                    Span::from("Esc").fg(Color::Yellow),
+                    Span::from(" | Up").fg(Color::Yellow),
+                    Span::from(" | "),
+                    Span::from("Down").fg(Color::Yellow),
+                    Span::from(" | "),
+                    Span::from("Enter").fg(Color::Yellow),
+                    Span::from(": Restore"),
+                ]);
+
+                render_status_bar(f, chunks_vertical[1], help, &self.app_state.error);
+            }
+            State::Statistics => {
+                // Create a vertical layout with 2 chunks
+                let chunks_vertical = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(3), Constraint::Length(1)])
+                    .split(f.area());
+
+                // Render the statistics widget in the top chunk of the vertical layout
+                self.app_state
+                    .statistics_widget
+                    .render(f, chunks_vertical[0]);
+
+                // Create the help line
+                let help = Line::from_iter([
+                    Span::from("Back: "),
+                    Span::from("Esc").fg(Color::Yellow),
+                    Span::from(" | "),
+                    Span::from("Left").fg(Color::Yellow),
+                    Span::from(" / "),
+                    Span::from("Right").fg(Color::Yellow),
+                    Span::from(": Date range"),
+                ]);
+
+                render_status_bar(f, chunks_vertical[1], help, &self.app_state.error);
+            }
+            State::Calendar => {
+                // Create a vertical layout with 2 chunks
+                let chunks_vertical = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(3), Constraint::Length(1)])
+                    .split(f.area());
+
+                // Render the calendar widget in the top chunk of the vertical layout
+                self.app_state.calendar_widget.render(f, chunks_vertical[0]);
+
+                // Create the help line
+                let help = Line::from_iter([
+                    Span::from("Back: "),
+                    Span::from("Esc").fg(Color::Yellow),
+                    Span::from(" | "),
+                    Span::from("Left").fg(Color::Yellow),
+                    Span::from(" / "),
+                    Span::from("Right").fg(Color::Yellow),
+                    Span::from(": Year or month | "),
+                    Span::from("m").fg(Color::Yellow),
+                    Span::from(": Month / year view"),
+                ]);
+
+                render_status_bar(f, chunks_vertical[1], help, &self.app_state.error);
+            }
         })?;
 
         Ok(())
     }
 }
+
+/// Render the help line and the error message in the status bar.
+fn render_status_bar(f: &mut Frame, area: Rect, help: Line, error: &str) {
+    // Create a horizontal layout with 3 chunks
+    let status_bar = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Fill(4),
+            Constraint::Length(1),
+            Constraint::Fill(2),
+        ])
+        .split(area);
+
+    // Render the help line in the left chunk of the status bar
+    f.render_widget(help.alignment(Alignment::Center), status_bar[0]);
+
+    // Render a vertical separator in the middle chunk of the status bar
+    f.render_widget(Line::from("┃"), status_bar[1]);
+
+    // Render the error message in the right chunk of the status bar. Warnings are yellow
+    let color = if error.starts_with("Warning:") {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    f.render_widget(
+        Line::from_iter([Span::from(" "), Span::from(error).fg(color)])
+            .alignment(Alignment::Center),
+        status_bar[2],
+    );
+}
@@ -1,7 +1,21 @@
+mod backups_widget;
+mod calendar_widget;
+mod confirm_dialog;
+mod date_picker;
+mod lesson_form;
+mod lesson_picker;
 mod lesson_widget;
 mod lessons_widget;
+mod statistics_widget;
 mod text_box;
 
+pub use backups_widget::BackupsWidget;
+pub use calendar_widget::CalendarWidget;
+pub use confirm_dialog::ConfirmDialog;
+pub use date_picker::DatePicker;
+pub use lesson_form::LessonForm;
+pub use lesson_picker::LessonPicker;
 pub use lesson_widget::LessonWidget;
 pub use lessons_widget::LessonsWidget;
+pub use statistics_widget::StatisticsWidget;
 pub use text_box::TextBox;
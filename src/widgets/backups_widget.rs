@@ -0,0 +1,198 @@
+use log::error;
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+use rusqlite::Connection;
+
+use crate::{
+    state::{State, SubState},
+    tipp10::{
+        backup::{self, BackupInfo},
+        Session,
+    },
+    tipp10w::{EventResult, ResultError},
+};
+
+use super::ConfirmDialog;
+
+// This widget is responsible for listing the backups and restoring one of them
+pub struct BackupsWidget {
+    pub ptr: usize,
+    pub backups: Vec<BackupInfo>,
+    confirm_dialog: Option<ConfirmDialog>,
+}
+impl Default for BackupsWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BackupsWidget {
+    pub fn new() -> Self {
+        Self {
+            ptr: 0,
+            backups: Vec::new(),
+            confirm_dialog: None,
+        }
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(" Backups ");
+
+        // Subtract 2 from the height to account for the border
+        let height = area.height as usize;
+        let lines_to_subtract = if 0 < self.ptr as isize + 3 - height as isize {
+            self.ptr + 3 - height
+        } else {
+            0
+        };
+
+        let mut lines = Vec::new();
+        for (i, backup) in self.backups.iter().enumerate() {
+            let lessons = match backup.lessons {
+                Some(lessons) => format!("{:<6}", lessons),
+                None => format!("{:<6}", "?"),
+            };
+
+            let line = Line::from_iter([
+                Span::from("[ "),
+                Span::from("Timestamp: "),
+                Span::from(format!("{:<14}", backup.timestamp)).fg(Color::Yellow),
+                Span::from(" | "),
+                Span::from("Lessons: "),
+                Span::from(lessons).fg(Color::Yellow),
+                Span::from(" | "),
+                Span::from("File: "),
+                Span::from(format!("{:<32}", backup.get_file_name())).fg(Color::Yellow),
+                Span::from(" ]"),
+            ])
+            .alignment(Alignment::Center);
+
+            // Highlight the selected backup
+            if i == self.ptr {
+                lines.push(line.bg(Color::DarkGray).fg(Color::Black));
+            } else {
+                lines.push(line);
+            };
+        }
+
+        if self.backups.is_empty() {
+            lines.push(Line::from("No backups found!").alignment(Alignment::Center));
+        };
+
+        // Remove the number of lines calculated in lines_to_subtract
+        if lines_to_subtract > 0 {
+            lines.drain(0..lines_to_subtract);
+        };
+
+        f.render_widget(Paragraph::new(lines).block(block), area);
+
+        if let Some(confirm_dialog) = &self.confirm_dialog {
+            confirm_dialog.render(f, area);
+        };
+    }
+
+    pub fn handle_events(
+        &mut self,
+        event: Event,
+        conn: &mut Connection,
+        session: &mut Session,
+    ) -> EventResult {
+        // The confirmation takes all events while it is open
+        if let Some(confirm_dialog) = &self.confirm_dialog {
+            return match confirm_dialog.handle_events(&event) {
+                EventResult::Submit => self.restore_selected(conn, session),
+                EventResult::Cancel => {
+                    self.confirm_dialog = None;
+                    EventResult::None(ResultError::None)
+                }
+                event_result => event_result,
+            };
+        };
+
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Up => {
+                        if self.ptr > 0 {
+                            self.ptr -= 1;
+                        };
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Down => {
+                        if self.ptr + 1 < self.backups.len() {
+                            self.ptr += 1;
+                        };
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Enter => {
+                        let backup = match self.backups.get(self.ptr) {
+                            Some(backup) => backup,
+                            None => return EventResult::None(ResultError::NoBackups),
+                        };
+
+                        // Ask before the lessons are replaced by the backup
+                        self.confirm_dialog = Some(ConfirmDialog::new(
+                            "Restore backup?",
+                            vec![
+                                Line::from(backup.get_file_name()),
+                                Line::from(
+                                    "All lessons will be replaced by the lessons of the backup!",
+                                ),
+                            ],
+                        ));
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Esc => EventResult::SetState(State::Menu(SubState::None)),
+                    _ => EventResult::None(ResultError::None),
+                }
+            }
+            _ => EventResult::None(ResultError::None),
+        }
+    }
+
+    /// Restore the selected backup and go back to the lessons
+    fn restore_selected(&mut self, conn: &mut Connection, session: &mut Session) -> EventResult {
+        let backup = match self.backups.get(self.ptr) {
+            Some(backup) => backup,
+            None => return EventResult::None(ResultError::NoBackups),
+        };
+
+        match backup::restore_backup(conn, session, &backup.path) {
+            Ok(_) => {
+                self.confirm_dialog = None;
+                EventResult::SetState(State::Menu(SubState::None))
+            }
+            Err(e) => {
+                error!("Could not restore backup! Error: {}", e);
+                EventResult::None(ResultError::SQLite)
+            }
+        }
+    }
+
+    /// Get the backups of the database
+    pub fn update_backups(&mut self, conn: &Connection) -> EventResult {
+        self.ptr = 0;
+        self.backups = match backup::list_backups(conn) {
+            Ok(backups) => backups,
+            Err(e) => {
+                error!("Could not list backups! Error: {}", e);
+                self.backups.clear();
+                return EventResult::None(ResultError::SQLite);
+            }
+        };
+
+        EventResult::None(ResultError::None)
+    }
+}
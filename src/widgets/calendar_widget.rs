@@ -0,0 +1,383 @@
+use chrono::{Datelike, Local, Months, NaiveDate};
+use log::error;
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+use rusqlite::Connection;
+
+use crate::{
+    state::{State, SubState},
+    tipp10::{
+        self,
+        calendar::{DateSpan, PracticeCalendar},
+    },
+    tipp10w::{EventResult, ResultError},
+};
+
+/// The names of the months for the labels of the year view.
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The names of the weekdays starting at Monday.
+const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// The colors of the practice levels from no practice to the most practice.
+const LEVEL_COLORS: [Color; 5] = [
+    Color::DarkGray,
+    Color::Indexed(22),
+    Color::Indexed(28),
+    Color::Indexed(34),
+    Color::Indexed(40),
+];
+
+// This widget is responsible for showing the practice time per day as a calendar heatmap
+pub struct CalendarWidget {
+    pub calendar: PracticeCalendar,
+    /// The first day of the shown month. The whole year of it is shown in the year view.
+    pub month: NaiveDate,
+    /// Show a single month instead of the whole year.
+    pub is_month_view: bool,
+}
+impl Default for CalendarWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalendarWidget {
+    pub fn new() -> Self {
+        Self {
+            calendar: PracticeCalendar::default(),
+            month: get_first_of_month(Local::now().date_naive()),
+            is_month_view: false,
+        }
+    }
+
+    /// Load the lessons from the database and show the current month.
+    pub fn update_lessons(&mut self, conn: &Connection) -> EventResult {
+        let lessons = match tipp10::get_lessons(conn) {
+            Ok(lessons) => lessons,
+            Err(e) => {
+                error!("Could not get the lessons for the calendar! Error: {}", e);
+                return EventResult::None(ResultError::SQLite);
+            }
+        };
+
+        self.calendar = PracticeCalendar::new(&lessons);
+        self.month = get_first_of_month(Local::now().date_naive());
+
+        if lessons.is_empty() {
+            return EventResult::None(ResultError::NoLessons);
+        };
+
+        EventResult::None(ResultError::None)
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let title = if self.is_month_view {
+            format!(
+                " {} {} ",
+                MONTH_NAMES[self.month.month0() as usize],
+                self.month.year()
+            )
+        } else {
+            format!(" {} ", self.month.year())
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(" Calendar ")
+            .title(Line::from(title).right_aligned());
+
+        let today = Local::now().date_naive();
+        let highlight = Highlight {
+            longest_streak: self.calendar.get_longest_streak(),
+            current_streak: self.calendar.get_current_streak(today),
+            longest_gap: self.calendar.get_longest_gap(),
+        };
+
+        let (start, end) = self.get_shown_days();
+        let mut lines = vec![self.get_summary(start, end, &highlight), Line::from("")];
+        if self.is_month_view {
+            lines.extend(self.get_month_lines(&highlight));
+        } else {
+            lines.extend(self.get_year_lines(&highlight));
+        };
+        lines.push(Line::from(""));
+        lines.extend(get_legend());
+
+        f.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(block),
+            area,
+        );
+    }
+
+    /// `Left` and `Right` move by a month or a year, `m` switches between the month and the year
+    /// view and `Esc` goes back to the lessons.
+    pub fn handle_events(&mut self, event: &Event) -> EventResult {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                let months = Months::new(if self.is_month_view { 1 } else { 12 });
+
+                match key_event.code {
+                    KeyCode::Left => {
+                        self.month = self.month.checked_sub_months(months).unwrap_or(self.month);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Right => {
+                        self.month = self.month.checked_add_months(months).unwrap_or(self.month);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('m') => {
+                        self.is_month_view = !self.is_month_view;
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Esc => EventResult::SetState(State::Menu(SubState::None)),
+                    _ => EventResult::None(ResultError::None),
+                }
+            }
+            _ => EventResult::None(ResultError::None),
+        }
+    }
+
+    /// Get the first and the last day of the shown month or year.
+    fn get_shown_days(&self) -> (NaiveDate, NaiveDate) {
+        if self.is_month_view {
+            let end = self
+                .month
+                .checked_add_months(Months::new(1))
+                .and_then(|date| date.pred_opt())
+                .unwrap_or(self.month);
+            (self.month, end)
+        } else {
+            let year = self.month.year();
+            (
+                NaiveDate::from_ymd_opt(year, 1, 1).unwrap_or(self.month),
+                NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(self.month),
+            )
+        }
+    }
+
+    /// Get the line with the practice of the shown days and the streaks.
+    fn get_summary(&self, start: NaiveDate, end: NaiveDate, highlight: &Highlight) -> Line<'_> {
+        let seconds = self.calendar.get_total_seconds(start, end);
+        let days = |days: Option<usize>| format!("{} days", days.unwrap_or(0));
+
+        Line::from_iter([
+            Span::from("Practice: "),
+            Span::from(format!("{}:{:02} h", seconds / 3600, seconds % 3600 / 60))
+                .fg(Color::Yellow),
+            Span::from(" on "),
+            Span::from(self.calendar.get_practice_days(start, end).to_string()).fg(Color::Yellow),
+            Span::from(" days | Current streak: "),
+            Span::from(days(
+                highlight.current_streak.map(|streak| streak.get_days()),
+            ))
+            .fg(Color::Yellow),
+            Span::from(" | Longest streak: "),
+            Span::from(days(
+                highlight.longest_streak.map(|streak| streak.get_days()),
+            ))
+            .fg(Color::Yellow),
+            Span::from(" | Longest gap: "),
+            Span::from(days(highlight.longest_gap.map(|gap| gap.get_days()))).fg(Color::Red),
+        ])
+    }
+
+    /// Get the lines of the year view. Every column is a week starting at Monday.
+    fn get_year_lines(&self, highlight: &Highlight) -> Vec<Line<'_>> {
+        let (start, end) = self.get_shown_days();
+        let first_monday = start - chrono::Days::new(start.weekday().num_days_from_monday() as u64);
+        let weeks = (end - first_monday).num_days() as usize / 7 + 1;
+
+        // Put the name of every month above the week of its first day
+        let mut month_labels = vec![' '; weeks * 2];
+        for (i, name) in MONTH_NAMES.iter().enumerate() {
+            let first = match NaiveDate::from_ymd_opt(start.year(), i as u32 + 1, 1) {
+                Some(first) => first,
+                None => continue,
+            };
+            let column = (first - first_monday).num_days() as usize / 7 * 2;
+            for (j, c) in name.chars().enumerate() {
+                if let Some(label) = month_labels.get_mut(column + j) {
+                    *label = c;
+                };
+            }
+        }
+
+        let mut lines = vec![Line::from(format!(
+            "    {}",
+            month_labels.into_iter().collect::<String>()
+        ))];
+        for (weekday, name) in WEEKDAY_NAMES.iter().enumerate() {
+            let mut spans = vec![Span::from(format!("{} ", name))];
+            for week in 0..weeks {
+                let date = first_monday + chrono::Days::new((week * 7 + weekday) as u64);
+                if date < start || date > end {
+                    spans.push(Span::from("  "));
+                } else {
+                    spans.push(self.get_day_span(date, "■", highlight));
+                    spans.push(Span::from(" "));
+                };
+            }
+
+            lines.push(Line::from(spans));
+        }
+
+        lines
+    }
+
+    /// Get the lines of the month view. Every line is a week starting at Monday.
+    fn get_month_lines(&self, highlight: &Highlight) -> Vec<Line<'_>> {
+        let (start, end) = self.get_shown_days();
+
+        let mut lines = vec![Line::from(
+            WEEKDAY_NAMES
+                .iter()
+                .map(|name| format!("{:<9}", name))
+                .collect::<String>(),
+        )];
+
+        let mut spans = vec![Span::from(
+            " ".repeat(9 * start.weekday().num_days_from_monday() as usize),
+        )];
+        let mut date = start;
+        while date <= end {
+            let minutes = self.calendar.get_seconds(date) / 60;
+            let text = format!("{:>2} {:>4}", date.day(), format!("{}m", minutes));
+            spans.push(self.get_day_span(date, &text, highlight));
+            spans.push(Span::from("  "));
+
+            // Start a new line after Sunday
+            if date.weekday().num_days_from_monday() == 6 {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+            };
+
+            date = match date.succ_opt() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+        if !spans.is_empty() {
+            // Fill the last week so the weekdays stay below their names
+            let missing = 6 - end.weekday().num_days_from_monday() as usize;
+            spans.push(Span::from(" ".repeat(9 * missing)));
+            lines.push(Line::from(spans));
+        };
+
+        lines
+    }
+
+    /// Get the span of a day colored by its practice time.
+    fn get_day_span(&self, date: NaiveDate, text: &str, highlight: &Highlight) -> Span<'static> {
+        let seconds = self.calendar.get_seconds(date);
+        let span = Span::from(text.to_string());
+
+        if seconds == 0 {
+            // Days of the longest gap are red
+            return match highlight.longest_gap {
+                Some(gap) if gap.contains(date) => span.fg(Color::Red),
+                _ => span.fg(LEVEL_COLORS[0]),
+            };
+        };
+
+        let span = span.fg(LEVEL_COLORS[get_level(seconds)]);
+        let in_streak =
+            |streak: Option<DateSpan>| streak.is_some_and(|streak| streak.contains(date));
+        if in_streak(highlight.current_streak) || in_streak(highlight.longest_streak) {
+            span.bold().underlined()
+        } else {
+            span
+        }
+    }
+}
+
+/// The streaks and the gap that are highlighted.
+struct Highlight {
+    longest_streak: Option<DateSpan>,
+    current_streak: Option<DateSpan>,
+    longest_gap: Option<DateSpan>,
+}
+
+/// Get the practice level of a day from 1 to 4.
+fn get_level(seconds: usize) -> usize {
+    match seconds / 60 {
+        0..=9 => 1,
+        10..=29 => 2,
+        30..=59 => 3,
+        _ => 4,
+    }
+}
+
+/// Get the first day of the month of the date.
+fn get_first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Get the lines explaining the colors.
+fn get_legend() -> Vec<Line<'static>> {
+    let mut levels = vec![Span::from("Less ")];
+    levels.extend(LEVEL_COLORS.iter().map(|color| Span::from("■ ").fg(*color)));
+    levels.push(Span::from("More (0, <10, <30, <60, 60+ minutes)"));
+
+    vec![
+        Line::from(levels),
+        Line::from_iter([
+            Span::from("■").bold().underlined(),
+            Span::from(": current and longest streak | "),
+            Span::from("■").fg(Color::Red),
+            Span::from(": longest gap"),
+        ]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_handle_events() {
+        let mut widget = CalendarWidget::new();
+        widget.month = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+
+        widget.handle_events(&key(KeyCode::Left));
+        assert_eq!(widget.month, NaiveDate::from_ymd_opt(2023, 3, 1).unwrap());
+
+        widget.handle_events(&key(KeyCode::Char('m')));
+        widget.handle_events(&key(KeyCode::Right));
+        assert_eq!(widget.month, NaiveDate::from_ymd_opt(2023, 4, 1).unwrap());
+        assert_eq!(
+            widget.get_shown_days(),
+            (
+                NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 4, 30).unwrap()
+            )
+        );
+
+        assert_eq!(
+            widget.handle_events(&key(KeyCode::Esc)),
+            EventResult::SetState(State::Menu(SubState::None))
+        );
+    }
+
+    #[test]
+    fn test_get_level() {
+        assert_eq!(get_level(60), 1);
+        assert_eq!(get_level(600), 2);
+        assert_eq!(get_level(3600), 4);
+    }
+}
@@ -0,0 +1,131 @@
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::tipp10w::{EventResult, ResultError};
+
+/// A modal popup that asks the user to confirm a destructive action.
+#[derive(Debug)]
+pub struct ConfirmDialog {
+    title: String,
+    lines: Vec<Line<'static>>,
+}
+
+impl ConfirmDialog {
+    /// Creates a new instance of ConfirmDialog with the lines describing what will happen.
+    pub fn new(title: &str, lines: Vec<Line<'static>>) -> Self {
+        Self {
+            title: format!(" {} ", title),
+            lines,
+        }
+    }
+
+    /// Render the dialog centered on top of the area.
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let mut lines = self.lines.clone();
+        lines.push(Line::from(""));
+        lines.push(
+            Line::from_iter([
+                Span::from("y").fg(Color::Yellow),
+                Span::from(": Confirm | "),
+                Span::from("n").fg(Color::Yellow),
+                Span::from(" / "),
+                Span::from("Esc").fg(Color::Yellow),
+                Span::from(": Cancel"),
+            ])
+            .alignment(Alignment::Center),
+        );
+
+        // Add 4 to the size to account for the border and the padding
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 4;
+        let height = lines.len() as u16 + 2;
+        let popup_area = get_centered_area(area, width, height);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(self.title.clone())
+            .fg(Color::Red);
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(
+            Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .block(block),
+            popup_area,
+        );
+    }
+
+    /// Only `y` confirms the action. Every other key is ignored, except `n` and `Esc` which cancel it.
+    pub fn handle_events(&self, event: &Event) -> EventResult {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => EventResult::Submit,
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => EventResult::Cancel,
+                    _ => EventResult::None(ResultError::None),
+                }
+            }
+            _ => EventResult::None(ResultError::None),
+        }
+    }
+}
+
+/// Get an area of the given size in the center of the area. The size is clamped to the area.
+pub(super) fn get_centered_area(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_handle_events() {
+        let dialog = ConfirmDialog::new("Delete", vec![Line::from("Lesson")]);
+        assert_eq!(
+            dialog.handle_events(&key(KeyCode::Char('y'))),
+            EventResult::Submit
+        );
+        assert_eq!(
+            dialog.handle_events(&key(KeyCode::Esc)),
+            EventResult::Cancel
+        );
+        assert_eq!(
+            dialog.handle_events(&key(KeyCode::Char('n'))),
+            EventResult::Cancel
+        );
+
+        // Enter does not confirm, so a repeated key press can not delete by accident
+        assert_eq!(
+            dialog.handle_events(&key(KeyCode::Enter)),
+            EventResult::None(ResultError::None)
+        );
+    }
+
+    #[test]
+    fn test_get_centered_area() {
+        let area = Rect::new(0, 0, 100, 20);
+        assert_eq!(get_centered_area(area, 40, 10), Rect::new(30, 5, 40, 10));
+        assert_eq!(get_centered_area(area, 200, 30), area);
+    }
+}
@@ -0,0 +1,148 @@
+use chrono::{Datelike, Days, Local, Months, NaiveDateTime};
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+    Frame,
+};
+
+use crate::tipp10w::{EventResult, ResultError};
+
+use super::confirm_dialog::get_centered_area;
+
+/// The short names of the weekdays starting at Monday.
+const WEEKDAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// A popup calendar of a month to choose a date from. The time of the date is kept.
+pub struct DatePicker {
+    datetime: NaiveDateTime,
+}
+
+impl DatePicker {
+    /// Creates a new instance of DatePicker with the date selected.
+    pub fn new(datetime: NaiveDateTime) -> Self {
+        Self { datetime }
+    }
+
+    /// Get the chosen date with the time it was opened with.
+    pub fn get_datetime(&self) -> NaiveDateTime {
+        self.datetime
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let selected = self.datetime.date();
+        let today = Local::now().date_naive();
+        let first = selected.with_day(1).unwrap_or(selected);
+
+        let mut lines = vec![
+            Line::from(first.format("%B %Y").to_string())
+                .bold()
+                .alignment(Alignment::Center),
+            Line::from(WEEKDAY_NAMES.join(" ")).fg(Color::DarkGray),
+        ];
+
+        let mut spans = vec![Span::from(
+            " ".repeat(3 * first.weekday().num_days_from_monday() as usize),
+        )];
+        let mut date = first;
+        while date.month() == first.month() {
+            let span = Span::from(format!("{:>2}", date.day()));
+            spans.push(if date == selected {
+                span.fg(Color::Black).bg(Color::Yellow)
+            } else if date == today {
+                span.fg(Color::Yellow).underlined()
+            } else {
+                span
+            });
+            spans.push(Span::from(" "));
+
+            // Start a new line after Sunday
+            if date.weekday().num_days_from_monday() == 6 {
+                lines.push(Line::from(std::mem::take(&mut spans)));
+            };
+
+            date = match date.succ_opt() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+        if spans.len() > 1 {
+            lines.push(Line::from(spans));
+        };
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .padding(Padding::horizontal(1))
+            .title(" Choose date ")
+            .title_bottom(format!(" Time: {} ", self.datetime.format("%H:%M:%S")));
+
+        // A week takes 20 characters, add 4 to the width to account for the border and padding
+        let popup_area = get_centered_area(area, 20 + 4, lines.len() as u16 + 2);
+        f.render_widget(Clear, popup_area);
+        f.render_widget(Paragraph::new(lines).block(block), popup_area);
+    }
+
+    /// `Left` and `Right` move by a day, `Up` and `Down` by a week and `PageUp` and `PageDown` by
+    /// a month. Returns Submit on `Enter` and Cancel on `Esc`.
+    pub fn handle_events(&mut self, event: &Event) -> EventResult {
+        let key_event = match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => key_event,
+            _ => return EventResult::None(ResultError::None),
+        };
+
+        let date = self.datetime.date();
+        let moved = match key_event.code {
+            KeyCode::Enter => return EventResult::Submit,
+            KeyCode::Esc => return EventResult::Cancel,
+            KeyCode::Left => date.checked_sub_days(Days::new(1)),
+            KeyCode::Right => date.checked_add_days(Days::new(1)),
+            KeyCode::Up => date.checked_sub_days(Days::new(7)),
+            KeyCode::Down => date.checked_add_days(Days::new(7)),
+            KeyCode::PageUp => date.checked_sub_months(Months::new(1)),
+            KeyCode::PageDown => date.checked_add_months(Months::new(1)),
+            _ => return EventResult::None(ResultError::None),
+        };
+
+        match moved {
+            Some(date) => {
+                self.datetime = date.and_time(self.datetime.time());
+                EventResult::None(ResultError::None)
+            }
+            None => EventResult::None(ResultError::OutOfBounds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
+
+    use crate::tipp10::get_naive_datetime_from_tipp10_str;
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_handle_events() {
+        let datetime = get_naive_datetime_from_tipp10_str("20240131183000").unwrap();
+        let mut date_picker = DatePicker::new(datetime);
+
+        date_picker.handle_events(&key(KeyCode::Right));
+        date_picker.handle_events(&key(KeyCode::Down));
+        date_picker.handle_events(&key(KeyCode::PageDown));
+        assert_eq!(
+            date_picker.handle_events(&key(KeyCode::Enter)),
+            EventResult::Submit
+        );
+
+        // The time is kept
+        let expected = get_naive_datetime_from_tipp10_str("20240308183000").unwrap();
+        assert_eq!(date_picker.get_datetime(), expected);
+    }
+}
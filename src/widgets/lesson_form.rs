@@ -0,0 +1,898 @@
+use chrono::Local;
+use log::error;
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Alignment, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+    Frame,
+};
+use rusqlite::Connection;
+
+use crate::{
+    tipp10::{
+        self, date_input, duration,
+        validation::{self, Issue, IssueField, Severity, ValidationRules},
+        Lesson, LessonCatalogue, LessonType, SQLiteError, Session,
+    },
+    tipp10w::{EventResult, ResultError},
+};
+
+use super::{confirm_dialog::get_centered_area, ConfirmDialog, DatePicker, LessonPicker, TextBox};
+
+/// The fields of a lesson that can be edited in the form.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FormField {
+    Lesson,
+    Timestamp,
+    Duration,
+    Strokes,
+    Errors,
+    Tokens,
+    Type,
+}
+impl FormField {
+    /// Every field in the order they are shown.
+    const ALL: [FormField; 7] = [
+        FormField::Lesson,
+        FormField::Timestamp,
+        FormField::Duration,
+        FormField::Strokes,
+        FormField::Errors,
+        FormField::Tokens,
+        FormField::Type,
+    ];
+
+    fn get_label(&self) -> &'static str {
+        match self {
+            FormField::Lesson => "Lesson",
+            FormField::Timestamp => "Timestamp",
+            FormField::Duration => "Duration",
+            FormField::Strokes => "Strokes",
+            FormField::Errors => "Errors",
+            FormField::Tokens => "Tokens",
+            FormField::Type => "Type",
+        }
+    }
+
+    /// Get the position of the field in the form.
+    fn get_index(&self) -> usize {
+        FormField::ALL
+            .iter()
+            .position(|other| other == self)
+            .expect("Every field is in the form!")
+    }
+
+    /// Get the hint about the format of the field.
+    fn get_hint(&self) -> &'static str {
+        match self {
+            FormField::Lesson => "Enter: choose from the list",
+            FormField::Timestamp => "2024-03-05 14:30, yesterday 18:00, -2d",
+            FormField::Duration => "10:00, 1:05:00 or 10m",
+            FormField::Strokes | FormField::Errors | FormField::Tokens => "Number",
+            FormField::Type => "Training, Open text or Own",
+        }
+    }
+
+    /// Get the value of the field of the lesson as it is typed into the form.
+    fn get_value(&self, lesson: &Lesson) -> String {
+        match self {
+            FormField::Lesson => lesson
+                .number
+                .map_or(String::new(), |number| number.to_string()),
+            FormField::Timestamp => lesson.timestamp.to_string(),
+            FormField::Duration => duration::format_duration(lesson.duration),
+            FormField::Strokes => lesson.strokes.to_string(),
+            FormField::Errors => lesson.errors.to_string(),
+            FormField::Tokens => lesson.tokens.to_string(),
+            FormField::Type => lesson.lesson_type.get_name().to_string(),
+        }
+    }
+
+    /// Get the field that shows the problems of the value found by the validation.
+    fn from_issue_field(field: IssueField) -> FormField {
+        match field {
+            IssueField::Timestamp => FormField::Timestamp,
+            IssueField::Duration | IssueField::Cpm => FormField::Duration,
+            IssueField::Errors | IssueField::ErrorRate => FormField::Errors,
+        }
+    }
+
+    /// Write the typed value into the lesson. Returns the message why the value is not valid.
+    fn set_value(
+        &self,
+        lesson: &mut Lesson,
+        value: &str,
+        catalogue: &LessonCatalogue,
+    ) -> Result<(), String> {
+        let value = value.trim();
+        let number = || {
+            value
+                .parse::<usize>()
+                .map_err(|_| "Must be a whole number".to_string())
+        };
+
+        match self {
+            FormField::Lesson => {
+                // A lesson that is not in the catalogue is kept if nothing was entered
+                if value.is_empty() && lesson.number.is_none() {
+                    return Ok(());
+                };
+                let catalogue_lesson = value
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| catalogue.get(number))
+                    .ok_or(format!("Must be a lesson from 1 to {}", catalogue.len()))?;
+                lesson.set_catalogue_lesson(catalogue_lesson);
+            }
+            // Nothing entered is the current time
+            FormField::Timestamp if value.is_empty() => lesson.timestamp = tipp10::get_timestamp(),
+            // The stored timestamp is kept, even if it is not a time of the local time zone
+            FormField::Timestamp if value == lesson.timestamp.to_string() => (),
+            FormField::Timestamp => {
+                lesson.timestamp = date_input::get_timestamp_from_input(value)
+                    .map_err(|_| "Must be a date like 2024-03-05 14:30 or -2d".to_string())?;
+            }
+            FormField::Duration => {
+                lesson.duration = duration::parse_duration(value)
+                    .ok_or("Must be a duration like 10:00 or 10m".to_string())?;
+            }
+            FormField::Strokes => lesson.strokes = number()?,
+            FormField::Errors => lesson.errors = number()?,
+            FormField::Tokens => lesson.tokens = number()?,
+            // An unknown type is kept as it is shown
+            FormField::Type if value == lesson.lesson_type.get_name() => (),
+            FormField::Type => {
+                lesson.lesson_type = LessonType::try_from_name(value)
+                    .ok_or("Must be Training, Open text or Own".to_string())?;
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// A popup form that edits every field of a lesson at once. The lesson is only saved with
+/// `Ctrl+S` or the save button and unsaved changes are only discarded after asking.
+pub struct LessonForm {
+    /// The lesson as it was when the form was opened.
+    lesson: Lesson,
+    /// The lesson is not in the database yet and is appended when it is saved.
+    is_new: bool,
+    inputs: Vec<TextBox>,
+    /// The messages why the values of the fields are not valid.
+    errors: Vec<Option<String>>,
+    /// The rules the lesson of the form is validated with.
+    rules: ValidationRules,
+    /// The problems of the lesson of the form found by the validation.
+    issues: Vec<Issue>,
+    /// The focused field. The two indices after the fields are the save and the cancel button.
+    focus: usize,
+    /// The list the lesson is chosen from.
+    lesson_picker: Option<LessonPicker>,
+    /// The calendar the date of the timestamp is chosen from.
+    date_picker: Option<DatePicker>,
+    confirm_dialog: Option<ConfirmDialog>,
+}
+
+impl LessonForm {
+    /// Creates a new instance of LessonForm with the values of the lesson that are validated with
+    /// the rules.
+    pub fn new(lesson: &Lesson, rules: ValidationRules) -> Self {
+        let inputs = FormField::ALL
+            .iter()
+            .map(|field| {
+                let value = field.get_value(lesson);
+                let mut input = TextBox::with_preset(&value, None);
+                // The preset always fits, so the cursor can be moved to the end
+                let _ = input.set_ptr(value.chars().count());
+                input
+            })
+            .collect();
+
+        Self {
+            lesson: lesson.clone(),
+            is_new: false,
+            inputs,
+            errors: vec![None; FormField::ALL.len()],
+            rules,
+            issues: validation::validate_lesson(lesson, &rules, tipp10::get_timestamp()),
+            focus: 0,
+            lesson_picker: None,
+            date_picker: None,
+            confirm_dialog: None,
+        }
+    }
+
+    /// Creates a new instance of LessonForm for a lesson that is only appended to the database when
+    /// it is saved. The ID of the lesson is ignored.
+    pub fn for_new_lesson(lesson: &Lesson, rules: ValidationRules) -> Self {
+        Self {
+            is_new: true,
+            ..Self::new(lesson, rules)
+        }
+    }
+
+    /// Check if the lesson is appended instead of updated when it is saved.
+    pub fn is_new(&self) -> bool {
+        self.is_new
+    }
+
+    /// Get the ID of the lesson that is edited. A new lesson has its ID once it is saved.
+    pub fn get_id(&self) -> usize {
+        self.lesson.id
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, catalogue: &LessonCatalogue) {
+        let mut lines = vec![if self.is_new {
+            Line::from("New lesson").alignment(Alignment::Center)
+        } else {
+            Line::from(format!("ID: {} | {}", self.lesson.id, self.lesson.name))
+                .alignment(Alignment::Center)
+        }];
+        lines.push(Line::from(""));
+
+        for (i, field) in FormField::ALL.iter().enumerate() {
+            let focused = i == self.focus;
+            let mut spans = vec![if focused {
+                Span::from(format!("{:>10}: ", field.get_label()))
+                    .fg(Color::Yellow)
+                    .bold()
+            } else {
+                Span::from(format!("{:>10}: ", field.get_label()))
+            }];
+
+            // Only the focused field shows the cursor
+            let input = &self.inputs[i];
+            if focused {
+                spans.extend(input.get_line().spans);
+            } else {
+                spans.push(Span::from(input.get_buffer()).fg(Color::Yellow));
+            };
+
+            let width = input.get_buffer_ref().chars().count() + usize::from(focused);
+            spans.push(Span::from(" ".repeat(24usize.saturating_sub(width))));
+            // The lesson shows the name of the chosen lesson and the timestamp the date it is
+            // stored as instead of the hint
+            let name = match field {
+                FormField::Lesson => input
+                    .get_buffer_ref()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| catalogue.get(number))
+                    .map(|catalogue_lesson| catalogue_lesson.name.clone()),
+                FormField::Timestamp => {
+                    date_input::get_timestamp_from_input(input.get_buffer_ref())
+                        .ok()
+                        .and_then(tipp10::get_datetime_from_timestamp)
+                        .map(|datetime| format!("= {}", datetime.format("%Y-%m-%d %H:%M:%S")))
+                }
+                _ => None,
+            };
+            // Invalid values are red and problems of the lesson red or yellow for warnings
+            let issue = self
+                .issues
+                .iter()
+                .find(|issue| FormField::from_issue_field(issue.field) == *field);
+            spans.push(match (&self.errors[i], issue, name) {
+                (Some(message), _, _) => Span::from(message.clone()).fg(Color::Red),
+                (None, Some(issue), _) => {
+                    Span::from(issue.message.clone()).fg(match issue.severity {
+                        Severity::Warning => Color::Yellow,
+                        Severity::Error => Color::Red,
+                    })
+                }
+                (None, None, Some(name)) if !focused || *field == FormField::Timestamp => {
+                    Span::from(name)
+                }
+                (None, None, _) => Span::from(field.get_hint()).fg(Color::DarkGray),
+            });
+
+            lines.push(Line::from(spans));
+        }
+
+        lines.push(Line::from(""));
+        let button = |label: &str, i: usize| {
+            let span = Span::from(format!("[ {} ]", label));
+            if i == self.focus {
+                span.fg(Color::Black).bg(Color::Yellow)
+            } else {
+                span
+            }
+        };
+        let fields = FormField::ALL.len();
+        lines.push(
+            Line::from_iter([
+                button("Save", fields),
+                Span::from("  "),
+                button("Cancel", fields + 1),
+            ])
+            .alignment(Alignment::Center),
+        );
+
+        // Add 4 to the size to account for the border and the padding
+        let width = lines
+            .iter()
+            .map(|line| line.width())
+            .max()
+            .unwrap_or(0)
+            .max(60) as u16
+            + 4;
+        let height = lines.len() as u16 + 2;
+        let popup_area = get_centered_area(area, width, height);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .padding(Padding::horizontal(1))
+            .title(if self.is_new {
+                " Add lesson "
+            } else {
+                " Edit lesson "
+            })
+            .title_bottom(
+                Line::from(if self.has_changes() {
+                    " Unsaved changes "
+                } else {
+                    ""
+                })
+                .right_aligned(),
+            );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(Paragraph::new(lines).block(block), popup_area);
+
+        if let Some(lesson_picker) = &mut self.lesson_picker {
+            lesson_picker.render(f, area, catalogue);
+        };
+        if let Some(date_picker) = &self.date_picker {
+            date_picker.render(f, area);
+        };
+        if let Some(confirm_dialog) = &self.confirm_dialog {
+            confirm_dialog.render(f, area);
+        };
+    }
+
+    /// `Tab`, `Down` and `Enter` go to the next field, `Shift+Tab` and `Up` to the previous one.
+    /// `Enter` or typing on the lesson opens the list of lessons to choose from and `Ctrl+D` on the
+    /// timestamp the calendar.
+    /// `Ctrl+S` or `Enter` on the save button saves the lesson and returns Submit. `Esc` or the
+    /// cancel button closes the form and returns Cancel, after asking if there are changes.
+    pub fn handle_events(
+        &mut self,
+        event: &Event,
+        conn: &Connection,
+        session: &mut Session,
+        catalogue: &LessonCatalogue,
+    ) -> EventResult {
+        // The confirmation takes all events while it is open
+        if let Some(confirm_dialog) = &self.confirm_dialog {
+            return match confirm_dialog.handle_events(event) {
+                EventResult::Submit => EventResult::Cancel,
+                EventResult::Cancel => {
+                    self.confirm_dialog = None;
+                    EventResult::None(ResultError::None)
+                }
+                event_result => event_result,
+            };
+        };
+        if let Some(lesson_picker) = &mut self.lesson_picker {
+            return match lesson_picker.handle_events(event, catalogue) {
+                EventResult::Submit => {
+                    if let Some(number) = lesson_picker.get_selected() {
+                        self.set_input(FormField::Lesson, &number.to_string(), catalogue);
+                        self.focus += 1;
+                    };
+                    self.lesson_picker = None;
+                    EventResult::None(ResultError::None)
+                }
+                EventResult::Cancel => {
+                    self.lesson_picker = None;
+                    EventResult::None(ResultError::None)
+                }
+                event_result => event_result,
+            };
+        };
+        if let Some(date_picker) = &mut self.date_picker {
+            return match date_picker.handle_events(event) {
+                EventResult::Submit => {
+                    let timestamp = date_picker.get_datetime().format("%Y%m%d%H%M%S");
+                    self.set_input(FormField::Timestamp, &timestamp.to_string(), catalogue);
+                    self.date_picker = None;
+                    EventResult::None(ResultError::None)
+                }
+                EventResult::Cancel => {
+                    self.date_picker = None;
+                    EventResult::None(ResultError::None)
+                }
+                event_result => event_result,
+            };
+        };
+
+        let fields = FormField::ALL.len();
+        let is_lesson = self.focus == 0;
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.save(conn, session, catalogue)
+                    }
+                    KeyCode::Char('d')
+                        if key_event.modifiers.contains(KeyModifiers::CONTROL)
+                            && FormField::ALL.get(self.focus) == Some(&FormField::Timestamp) =>
+                    {
+                        // Start at the typed date or now
+                        let input = self.inputs[self.focus].get_buffer_ref();
+                        let datetime = date_input::get_timestamp_from_input(input)
+                            .ok()
+                            .and_then(tipp10::get_datetime_from_timestamp)
+                            .unwrap_or_else(Local::now);
+                        self.date_picker = Some(DatePicker::new(datetime.naive_local()));
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Esc => self.cancel(),
+                    KeyCode::Tab | KeyCode::Down => {
+                        self.focus = (self.focus + 1) % (fields + 2);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        self.focus = (self.focus + fields + 1) % (fields + 2);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Enter if self.focus == fields => self.save(conn, session, catalogue),
+                    KeyCode::Enter if self.focus == fields + 1 => self.cancel(),
+                    KeyCode::Enter if is_lesson => {
+                        let number = self.inputs[FormField::Lesson.get_index()]
+                            .get_buffer_ref()
+                            .parse()
+                            .ok();
+                        self.lesson_picker = Some(LessonPicker::new(catalogue, number));
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char(c) if is_lesson => {
+                        // Typing starts the filter of the list
+                        self.lesson_picker =
+                            Some(LessonPicker::with_filter(catalogue, &c.to_string()));
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Enter => {
+                        self.focus += 1;
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Left | KeyCode::Right if self.focus >= fields => {
+                        // Switch between the buttons
+                        self.focus = if self.focus == fields {
+                            fields + 1
+                        } else {
+                            fields
+                        };
+                        EventResult::None(ResultError::None)
+                    }
+                    _ if self.focus < fields && !is_lesson => self.handle_input(event, catalogue),
+                    _ => EventResult::None(ResultError::None),
+                }
+            }
+            Event::Paste(text) if is_lesson => {
+                self.lesson_picker = Some(LessonPicker::with_filter(catalogue, text));
+                EventResult::None(ResultError::None)
+            }
+            Event::Paste(_) if self.focus < fields => self.handle_input(event, catalogue),
+            _ => EventResult::None(ResultError::None),
+        }
+    }
+
+    /// Pass the event to the focused input and check its new value.
+    fn handle_input(&mut self, event: &Event, catalogue: &LessonCatalogue) -> EventResult {
+        let event_result = match self.inputs[self.focus].handle_events(event) {
+            Ok(event_result) => event_result,
+            Err(_) => EventResult::None(ResultError::TextBoxError),
+        };
+
+        let field = FormField::ALL[self.focus];
+        let mut lesson = self.lesson.clone();
+        self.errors[self.focus] = field
+            .set_value(
+                &mut lesson,
+                self.inputs[self.focus].get_buffer_ref(),
+                catalogue,
+            )
+            .err();
+        self.validate(catalogue);
+
+        event_result
+    }
+
+    /// Replace the value of the field and check it.
+    fn set_input(&mut self, field: FormField, value: &str, catalogue: &LessonCatalogue) {
+        let i = field.get_index();
+        self.inputs[i].set_buf(value);
+        // The value always fits, so the cursor can be moved to the end
+        let _ = self.inputs[i].set_ptr(value.chars().count());
+
+        let mut lesson = self.lesson.clone();
+        self.errors[i] = field.set_value(&mut lesson, value, catalogue).err();
+        self.validate(catalogue);
+    }
+
+    /// Check if any value differs from the lesson.
+    pub fn has_changes(&self) -> bool {
+        FormField::ALL
+            .iter()
+            .zip(&self.inputs)
+            .any(|(field, input)| field.get_value(&self.lesson) != input.get_buffer_ref())
+    }
+
+    /// Get the lesson with the values of the form and the messages why the values are not valid.
+    /// Fields with an invalid value keep the value of the lesson.
+    fn parse_lesson(&self, catalogue: &LessonCatalogue) -> (Lesson, Vec<Option<String>>) {
+        let mut lesson = self.lesson.clone();
+        let errors = FormField::ALL
+            .iter()
+            .zip(&self.inputs)
+            .map(|(field, input)| {
+                field
+                    .set_value(&mut lesson, input.get_buffer_ref(), catalogue)
+                    .err()
+            })
+            .collect();
+
+        // Tokens that were the same as the strokes follow them if they were not changed
+        let tokens = FormField::Tokens.get_value(&self.lesson);
+        if self.lesson.tokens == self.lesson.strokes
+            && self.inputs[FormField::Tokens.get_index()]
+                .get_buffer_ref()
+                .trim()
+                == tokens
+        {
+            lesson.tokens = lesson.strokes;
+        };
+
+        (lesson, errors)
+    }
+
+    /// Check the lesson of the form against the rules, so the problems are shown while typing.
+    fn validate(&mut self, catalogue: &LessonCatalogue) {
+        let (lesson, _) = self.parse_lesson(catalogue);
+        self.issues = validation::validate_lesson(&lesson, &self.rules, tipp10::get_timestamp());
+    }
+
+    /// Get the lesson with the values of the form. The errors of every field and the problems of
+    /// the lesson are updated.
+    fn get_lesson(&mut self, catalogue: &LessonCatalogue) -> Option<Lesson> {
+        let (lesson, errors) = self.parse_lesson(catalogue);
+        self.errors = errors;
+        self.issues = validation::validate_lesson(&lesson, &self.rules, tipp10::get_timestamp());
+
+        if self.errors.iter().any(Option::is_some) {
+            return None;
+        };
+
+        Some(lesson)
+    }
+
+    /// Get the warnings of the lesson of the form as the message shown in the status bar.
+    pub fn get_warning(&self) -> Option<ResultError> {
+        let warnings = self
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+            .map(|issue| issue.message.as_str())
+            .collect::<Vec<&str>>();
+        if warnings.is_empty() {
+            return None;
+        };
+
+        Some(ResultError::Warning(warnings.join(", ")))
+    }
+
+    /// Save the lesson. The first field with an invalid value or a problem that prevents saving
+    /// the lesson is focused if it can not be saved.
+    fn save(
+        &mut self,
+        conn: &Connection,
+        session: &mut Session,
+        catalogue: &LessonCatalogue,
+    ) -> EventResult {
+        let lesson = match self.get_lesson(catalogue) {
+            Some(lesson) => lesson,
+            None => {
+                self.focus = self
+                    .errors
+                    .iter()
+                    .position(Option::is_some)
+                    .unwrap_or(self.focus);
+                return EventResult::None(ResultError::WrongInput);
+            }
+        };
+
+        let errors = self
+            .issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .cloned()
+            .collect::<Vec<Issue>>();
+        if let Some(issue) = errors.first() {
+            self.focus = FormField::from_issue_field(issue.field).get_index();
+            let e = SQLiteError::InvalidLesson(errors);
+            return EventResult::None(ResultError::Invalid(e.to_string()));
+        };
+
+        let result = if self.is_new {
+            tipp10::append_lesson(conn, session, &lesson)
+        } else {
+            tipp10::update_lesson(conn, session, &lesson).map(|_| lesson.id)
+        };
+        match result {
+            Ok(id) => {
+                self.lesson.id = id;
+                EventResult::Submit
+            }
+            Err(e @ SQLiteError::InvalidLesson(_)) => {
+                EventResult::None(ResultError::Invalid(e.to_string()))
+            }
+            Err(e) => {
+                error!("Could not save lesson in database! Error: {}", e);
+                EventResult::None(ResultError::SQLite)
+            }
+        }
+    }
+
+    /// Close the form. Asks before discarding changes.
+    fn cancel(&mut self) -> EventResult {
+        if !self.has_changes() {
+            return EventResult::Cancel;
+        };
+
+        self.confirm_dialog = Some(ConfirmDialog::new(
+            "Discard changes?",
+            vec![Line::from("The changes of the lesson are not saved.")],
+        ));
+        EventResult::None(ResultError::None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ratatui::crossterm::event::KeyEvent;
+
+    use crate::tipp10::tests::{new_lesson, setup_test_db};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn type_text(form: &mut LessonForm, text: &str, catalogue: &LessonCatalogue) {
+        let conn = setup_test_db();
+        for c in text.chars() {
+            form.handle_events(
+                &key(KeyCode::Char(c)),
+                &conn,
+                &mut Session::default(),
+                catalogue,
+            );
+        }
+    }
+
+    #[test]
+    fn test_save() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = new_lesson(1, 100, 10, 60, 20240301120000);
+        tipp10::append_lesson(&conn, &mut session, &lesson).unwrap();
+
+        let mut form = LessonForm::new(
+            &tipp10::get_lesson(&conn, 0).unwrap(),
+            session.validation_rules,
+        );
+        assert!(!form.has_changes());
+
+        // Go to the strokes and replace them with an invalid value
+        for _ in 0..3 {
+            form.handle_events(&key(KeyCode::Tab), &conn, &mut session, &catalogue);
+        }
+        for _ in 0..3 {
+            form.handle_events(&key(KeyCode::Backspace), &conn, &mut session, &catalogue);
+        }
+        type_text(&mut form, "2x", &catalogue);
+        assert!(form.errors[3].is_some());
+
+        // The invalid value is focused instead of saving
+        form.focus = 0;
+        let save = Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(
+            form.handle_events(&save, &conn, &mut session, &catalogue),
+            EventResult::None(ResultError::WrongInput)
+        );
+        assert_eq!(form.focus, 3);
+
+        form.handle_events(&key(KeyCode::Backspace), &conn, &mut session, &catalogue);
+        type_text(&mut form, "00", &catalogue);
+        assert_eq!(
+            form.handle_events(&save, &conn, &mut session, &catalogue),
+            EventResult::Submit
+        );
+
+        // The tokens followed the strokes
+        let lesson = tipp10::get_lesson(&conn, 0).unwrap();
+        assert_eq!((lesson.strokes, lesson.tokens), (200, 200));
+    }
+
+    #[test]
+    fn test_keep_timestamp_of_time_change() {
+        let catalogue = LessonCatalogue::fallback();
+        env::set_var("TZ", "CET-1CEST,M3.5.0,M10.5.0/3");
+
+        // 2:30 happens twice when daylight saving time ends and is skipped when it starts
+        for timestamp in [20241027023000, 20240331023000] {
+            let lesson = new_lesson(1, 100, 10, 60, timestamp);
+            let form = LessonForm::new(&lesson, ValidationRules::default());
+            let (parsed, errors) = form.parse_lesson(&catalogue);
+            assert!(errors.iter().all(Option::is_none));
+            assert_eq!(parsed.timestamp, timestamp);
+        }
+
+        // A typed time that happens twice is the earlier one
+        assert_eq!(
+            date_input::get_timestamp_from_input("2024-10-27 02:30"),
+            Ok(20241027023000)
+        );
+        assert!(date_input::get_timestamp_from_input("2024-03-31 02:30").is_err());
+
+        env::remove_var("TZ");
+    }
+
+    #[test]
+    fn test_save_new_lesson() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = new_lesson(1, 0, 0, 600, 20240301120000);
+
+        // Nothing is written if the form is closed
+        let mut form = LessonForm::for_new_lesson(&lesson, session.validation_rules);
+        assert_eq!(
+            form.handle_events(&key(KeyCode::Esc), &conn, &mut session, &catalogue),
+            EventResult::Cancel
+        );
+        assert!(tipp10::get_lessons(&conn).unwrap().is_empty());
+
+        let mut form = LessonForm::for_new_lesson(&lesson, session.validation_rules);
+        form.set_input(FormField::Strokes, "1200", &catalogue);
+        let save = Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(
+            form.handle_events(&save, &conn, &mut session, &catalogue),
+            EventResult::Submit
+        );
+        let lessons = tipp10::get_lessons(&conn).unwrap();
+        assert_eq!(lessons.len(), 1);
+        assert_eq!(lessons[0].strokes, 1200);
+        assert_eq!(form.get_id(), lessons[0].id);
+    }
+
+    #[test]
+    fn test_cancel_asks_before_discarding() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let mut form = LessonForm::new(
+            &new_lesson(1, 100, 10, 60, 20240301120000),
+            ValidationRules::default(),
+        );
+
+        assert_eq!(
+            form.handle_events(&key(KeyCode::Esc), &conn, &mut session, &catalogue),
+            EventResult::Cancel
+        );
+
+        form.focus = 2;
+        type_text(&mut form, "8", &catalogue);
+        assert!(form.has_changes());
+        form.handle_events(&key(KeyCode::Esc), &conn, &mut session, &catalogue);
+        assert!(form.confirm_dialog.is_some());
+        form.handle_events(&key(KeyCode::Char('n')), &conn, &mut session, &catalogue);
+        assert!(form.confirm_dialog.is_none());
+
+        form.handle_events(&key(KeyCode::Esc), &conn, &mut session, &catalogue);
+        assert_eq!(
+            form.handle_events(&key(KeyCode::Char('y')), &conn, &mut session, &catalogue),
+            EventResult::Cancel
+        );
+    }
+
+    #[test]
+    fn test_choose_lesson() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let mut form = LessonForm::new(
+            &new_lesson(1, 100, 10, 60, 20240301120000),
+            ValidationRules::default(),
+        );
+
+        // Typing on the lesson filters the list and Enter chooses the lesson
+        type_text(&mut form, "numpad 2", &catalogue);
+        assert!(form.lesson_picker.is_some());
+        form.handle_events(&key(KeyCode::Enter), &conn, &mut session, &catalogue);
+        assert!(form.lesson_picker.is_none());
+        assert_eq!(form.inputs[0].get_buffer_ref(), "20");
+        assert_eq!(form.focus, 1);
+
+        // Esc closes the list without changing the lesson
+        form.focus = 0;
+        form.handle_events(&key(KeyCode::Enter), &conn, &mut session, &catalogue);
+        form.handle_events(&key(KeyCode::Up), &conn, &mut session, &catalogue);
+        form.handle_events(&key(KeyCode::Esc), &conn, &mut session, &catalogue);
+        assert!(form.lesson_picker.is_none());
+        assert_eq!(form.get_lesson(&catalogue).unwrap().number, Some(20));
+    }
+
+    #[test]
+    fn test_validate() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let lesson = new_lesson(1, 100, 10, 60, 20240301120000);
+        tipp10::append_lesson(&conn, &mut session, &lesson).unwrap();
+        let mut form = LessonForm::new(
+            &tipp10::get_lesson(&conn, 0).unwrap(),
+            session.validation_rules,
+        );
+
+        // More errors than strokes can not be saved and the errors are focused
+        form.set_input(FormField::Errors, "200", &catalogue);
+        assert!(form
+            .issues
+            .iter()
+            .any(|issue| issue.severity == Severity::Error));
+        let save = Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert!(matches!(
+            form.handle_events(&save, &conn, &mut session, &catalogue),
+            EventResult::None(ResultError::Invalid(_))
+        ));
+        assert_eq!(form.focus, 4);
+        assert_eq!(tipp10::get_lesson(&conn, 0).unwrap().errors, 10);
+
+        // A lesson that is only unlikely is saved with a warning
+        form.set_input(FormField::Errors, "10", &catalogue);
+        form.set_input(FormField::Duration, "5:00:00", &catalogue);
+        assert_eq!(
+            form.handle_events(&save, &conn, &mut session, &catalogue),
+            EventResult::Submit
+        );
+        assert!(matches!(form.get_warning(), Some(ResultError::Warning(_))));
+        assert_eq!(tipp10::get_lesson(&conn, 0).unwrap().duration, 5 * 3600);
+    }
+
+    #[test]
+    fn test_choose_date() {
+        let conn = setup_test_db();
+        let mut session = Session::default();
+        let catalogue = LessonCatalogue::fallback();
+        let mut form = LessonForm::new(
+            &new_lesson(1, 100, 10, 60, 20240301120000),
+            ValidationRules::default(),
+        );
+        form.focus = 1;
+
+        let calendar = Event::Key(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL));
+        form.handle_events(&calendar, &conn, &mut session, &catalogue);
+        assert!(form.date_picker.is_some());
+        form.handle_events(&key(KeyCode::Left), &conn, &mut session, &catalogue);
+        form.handle_events(&key(KeyCode::Enter), &conn, &mut session, &catalogue);
+        assert!(form.date_picker.is_none());
+        assert_eq!(form.inputs[1].get_buffer_ref(), "20240229120000");
+
+        // Other formats are stored in the tipp10 format
+        form.set_input(FormField::Timestamp, "2024-03-05 14:30", &catalogue);
+        assert_eq!(
+            form.get_lesson(&catalogue).unwrap().timestamp,
+            20240305143000
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::Rect,
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use crate::{
+    tipp10::{CatalogueLesson, LessonCatalogue},
+    tipp10w::{EventResult, ResultError},
+};
+
+use super::{confirm_dialog::get_centered_area, TextBox};
+
+/// The number of lessons `PageUp` and `PageDown` move by.
+const PAGE_SIZE: usize = 10;
+
+/// A popup list of the lessons of the catalogue. Typing filters the lessons by their number or
+/// name.
+pub struct LessonPicker {
+    filter: TextBox,
+    /// The numbers of the lessons that match the filter.
+    matches: Vec<usize>,
+    ptr: usize,
+    /// The scroll position of the list.
+    list_state: ListState,
+}
+
+impl LessonPicker {
+    /// Creates a new instance of LessonPicker with the lesson of the number selected.
+    pub fn new(catalogue: &LessonCatalogue, selected: Option<usize>) -> Self {
+        let mut lesson_picker = Self {
+            filter: TextBox::new(None),
+            matches: Vec::new(),
+            ptr: 0,
+            list_state: ListState::default(),
+        };
+        lesson_picker.apply_filter(catalogue);
+        if let Some(ptr) = selected.and_then(|number| {
+            lesson_picker
+                .matches
+                .iter()
+                .position(|other| *other == number)
+        }) {
+            lesson_picker.ptr = ptr;
+        };
+
+        lesson_picker
+    }
+
+    /// Creates a new instance of LessonPicker that is filtered by the text.
+    pub fn with_filter(catalogue: &LessonCatalogue, filter: &str) -> Self {
+        let mut lesson_picker = Self::new(catalogue, None);
+        lesson_picker.filter.set_buf(filter);
+        // The text always fits, so the cursor can be moved to the end
+        let _ = lesson_picker.filter.set_ptr(filter.chars().count());
+        lesson_picker.apply_filter(catalogue);
+
+        lesson_picker
+    }
+
+    /// Get the number of the selected lesson. None if no lesson matches the filter.
+    pub fn get_selected(&self) -> Option<usize> {
+        self.matches.get(self.ptr).copied()
+    }
+
+    pub fn render(&mut self, f: &mut Frame, area: Rect, catalogue: &LessonCatalogue) {
+        // Lessons of the lesson list also show the value they are stored with
+        let is_custom = catalogue.get_language().is_none();
+        let items = self
+            .matches
+            .iter()
+            .filter_map(|number| catalogue.get(*number))
+            .map(|lesson| {
+                let mut spans = vec![
+                    Span::from(format!("{:>3} ", lesson.number)).fg(Color::Yellow),
+                    Span::from(lesson.name.clone()),
+                ];
+                if is_custom {
+                    spans.push(
+                        Span::from(format!(" (stored as {})", lesson.user_lesson))
+                            .fg(Color::DarkGray),
+                    );
+                };
+
+                ListItem::new(Line::from(spans))
+            })
+            .collect::<Vec<ListItem>>();
+
+        let mut filter = vec![Span::from(" Filter: ")];
+        filter.extend(self.filter.get_line().spans);
+        filter.push(Span::from(" "));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(if is_custom {
+                " Lessons of the database "
+            } else {
+                " Lessons "
+            })
+            .title(
+                Line::from(format!(" {} of {} ", self.matches.len(), catalogue.len()))
+                    .right_aligned(),
+            )
+            .title_bottom(Line::from(filter));
+
+        // Add 4 to the width to account for the border and the filter
+        let width = items
+            .iter()
+            .map(|item| item.width())
+            .max()
+            .unwrap_or(0)
+            .max(40) as u16
+            + 4;
+        let height = (items.len().max(1) as u16 + 2).min(area.height.saturating_sub(2));
+        let popup_area = get_centered_area(area, width, height);
+        f.render_widget(Clear, popup_area);
+
+        if items.is_empty() {
+            f.render_widget(
+                Paragraph::new("No lesson matches the filter!").block(block),
+                popup_area,
+            );
+            return;
+        };
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style((Color::Black, Color::Yellow));
+        self.list_state.select(Some(self.ptr));
+        f.render_stateful_widget(list, popup_area, &mut self.list_state);
+    }
+
+    /// `Up`, `Down`, `PageUp` and `PageDown` move through the lessons and any other key edits the
+    /// filter. Returns Submit when a lesson is chosen with `Enter` and Cancel on `Esc`.
+    pub fn handle_events(&mut self, event: &Event, catalogue: &LessonCatalogue) -> EventResult {
+        let key_event = match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => key_event,
+            _ => return EventResult::None(ResultError::None),
+        };
+
+        match key_event.code {
+            KeyCode::Enter if self.get_selected().is_none() => {
+                EventResult::None(ResultError::WrongInput)
+            }
+            KeyCode::Enter => EventResult::Submit,
+            KeyCode::Esc => EventResult::Cancel,
+            KeyCode::Up => {
+                self.ptr = self.ptr.saturating_sub(1);
+                EventResult::None(ResultError::None)
+            }
+            KeyCode::Down => {
+                self.ptr = (self.ptr + 1).min(self.matches.len().saturating_sub(1));
+                EventResult::None(ResultError::None)
+            }
+            KeyCode::PageUp => {
+                self.ptr = self.ptr.saturating_sub(PAGE_SIZE);
+                EventResult::None(ResultError::None)
+            }
+            KeyCode::PageDown => {
+                self.ptr = (self.ptr + PAGE_SIZE).min(self.matches.len().saturating_sub(1));
+                EventResult::None(ResultError::None)
+            }
+            _ => {
+                let event_result = match self.filter.handle_events(event) {
+                    Ok(event_result) => event_result,
+                    Err(_) => EventResult::None(ResultError::TextBoxError),
+                };
+                self.apply_filter(catalogue);
+
+                event_result
+            }
+        }
+    }
+
+    /// Find the lessons that match the filter. The selected lesson stays selected if it matches.
+    fn apply_filter(&mut self, catalogue: &LessonCatalogue) {
+        let selected = self.get_selected();
+        let filter = self.filter.get_buffer_ref().trim().to_lowercase();
+        self.matches = catalogue
+            .iter()
+            .filter(|lesson| matches(lesson, &filter))
+            .map(|lesson| lesson.number)
+            .collect();
+
+        self.ptr = selected
+            .and_then(|number| self.matches.iter().position(|other| *other == number))
+            .unwrap_or(0);
+    }
+}
+
+/// Check if the number of the lesson starts with the lowercase filter or its name contains it.
+fn matches(lesson: &CatalogueLesson, filter: &str) -> bool {
+    filter.is_empty()
+        || lesson.number.to_string().starts_with(filter)
+        || lesson.name.to_lowercase().contains(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_filter() {
+        let catalogue = LessonCatalogue::fallback();
+        let mut lesson_picker = LessonPicker::new(&catalogue, Some(18));
+        assert_eq!(lesson_picker.get_selected(), Some(18));
+
+        // The selected lesson stays selected while it matches
+        lesson_picker.handle_events(&key(KeyCode::Char('1')), &catalogue);
+        assert_eq!(lesson_picker.get_selected(), Some(18));
+        assert!(lesson_picker
+            .matches
+            .iter()
+            .all(|number| *number >= 10 || *number == 1));
+
+        lesson_picker.handle_events(&key(KeyCode::Char('x')), &catalogue);
+        assert_eq!(lesson_picker.get_selected(), None);
+        assert_eq!(
+            lesson_picker.handle_events(&key(KeyCode::Enter), &catalogue),
+            EventResult::None(ResultError::WrongInput)
+        );
+
+        let mut lesson_picker = LessonPicker::with_filter(&catalogue, "SPECIAL");
+        assert_eq!(lesson_picker.matches, vec![15, 17]);
+        lesson_picker.handle_events(&key(KeyCode::Down), &catalogue);
+        lesson_picker.handle_events(&key(KeyCode::Down), &catalogue);
+        assert_eq!(lesson_picker.get_selected(), Some(17));
+        assert_eq!(
+            lesson_picker.handle_events(&key(KeyCode::Enter), &catalogue),
+            EventResult::Submit
+        );
+    }
+}
@@ -1,24 +1,63 @@
+use std::collections::BTreeSet;
+
 use log::error;
 use ratatui::{
-    crossterm::event::{Event, KeyCode, KeyEventKind},
-    layout::Rect,
-    widgets::{Block, BorderType, Borders, Paragraph},
+    crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::{
+        Block, BorderType, Borders, Cell, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Table, TableState,
+    },
     Frame,
 };
 use rusqlite::Connection;
 
 use crate::{
-    state::SubState,
-    tipp10,
+    state::{State, SubState},
+    tipp10::{
+        self,
+        bulk::{self, BulkAction},
+        duration,
+        filter::LessonFilter,
+        sort::{LessonSort, SortColumn},
+        Language, Lesson, LessonCatalogue, SQLiteError, Session,
+    },
     tipp10w::{EventResult, ResultError},
 };
 
-use super::{LessonWidget, TextBox};
+use super::{ConfirmDialog, LessonForm, LessonPicker, LessonWidget, TextBox};
 
 // This widget is responsible for rendering and handling events for the lessons
 pub struct LessonsWidget {
     pub ptr: usize,
+    /// The lessons that match the filter.
     pub lessons: Vec<LessonWidget>,
+    /// Every lesson of the database.
+    all_lessons: Vec<Lesson>,
+    /// The filter typed in the search.
+    filter: LessonFilter,
+    /// The lessons of the opened database that can be chosen.
+    catalogue: LessonCatalogue,
+    /// The column and direction the lessons are sorted by.
+    sort: LessonSort,
+    /// The scroll position of the table.
+    table_state: TableState,
+    /// The number of lessons that fit into the table.
+    page_height: usize,
+    /// The IDs of the marked lessons that bulk actions are applied to.
+    marked: BTreeSet<usize>,
+    /// The bulk action that is being confirmed or typed.
+    bulk_input: Option<BulkInput>,
+    /// The message about the last change, like the number of lessons a bulk action changed, until
+    /// it is shown.
+    notice: Option<ResultError>,
+    confirm_dialog: Option<ConfirmDialog>,
+    /// The form of the lesson that is being edited.
+    lesson_form: Option<LessonForm>,
+    /// The list the lesson of the bulk action is chosen from.
+    lesson_picker: Option<LessonPicker>,
 }
 impl Default for LessonsWidget {
     fn default() -> Self {
@@ -31,44 +70,127 @@ impl LessonsWidget {
         Self {
             ptr: 0,
             lessons: Vec::new(),
+            all_lessons: Vec::new(),
+            filter: LessonFilter::default(),
+            catalogue: LessonCatalogue::default(),
+            sort: LessonSort::default(),
+            table_state: TableState::default(),
+            page_height: 1,
+            marked: BTreeSet::new(),
+            bulk_input: None,
+            notice: None,
+            confirm_dialog: None,
+            lesson_form: None,
+            lesson_picker: None,
         }
     }
 
-    pub fn render(&self, f: &mut Frame, area: Rect, sub_state: &SubState, text_box: &mut TextBox) {
-        let block = Block::default()
+    pub fn render(
+        &mut self,
+        f: &mut Frame,
+        area: Rect,
+        sub_state: &SubState,
+        text_box: &mut TextBox,
+    ) {
+        let mut block = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Thick)
-            .title(" Lessons ");
+            .title(" Lessons ")
+            .title(self.get_language_title());
+        if !self.marked.is_empty() {
+            block = block.title(format!(" {} marked ", self.marked.len()));
+        };
 
-        // Subtract 2 from the height to account for the border
-        let height = area.height as usize;
-        let lines_to_subtract = if 0 < self.ptr as isize + 3 - height as isize {
-            self.ptr + 3 - height
-        } else {
-            0
+        // Show the search while typing it and the filter while it is applied
+        if let (SubState::Bulk, Some(bulk_input)) = (sub_state, &self.bulk_input) {
+            let mut prompt = vec![Span::from(format!(
+                " {} of {} lessons: ",
+                bulk_input.get_name(),
+                self.get_bulk_ids().len()
+            ))];
+            // The lesson is chosen from the list instead of typed
+            if self.lesson_picker.is_none() {
+                prompt.extend(text_box.get_line().spans);
+            };
+            prompt.push(Span::from(" "));
+            block = block.title_bottom(Line::from(prompt));
+        } else if let SubState::Search = sub_state {
+            let mut search = vec![Span::from(" Search: ")];
+            search.extend(text_box.get_line().spans);
+            search.push(Span::from(" "));
+            block = block.title_bottom(Line::from(search));
+        } else if !self.filter.is_empty() {
+            block = block.title_bottom(format!(" Filter: {} ", self.filter.get_query()));
         };
 
-        // Render each lesson
-        let mut lines = Vec::new();
-        for (i, lesson) in self.lessons.iter().enumerate() {
-            // Check if the lesson is selected
-            let selected = i == self.ptr;
+        let inner_area = block.inner(area);
+        let columns = get_columns(inner_area.width);
 
-            // Check if the lesson is being edited
-            let is_editing = match sub_state {
-                SubState::Edit(id) => *id == lesson.lesson.id,
-                _ => false,
-            };
+        // Subtract 1 from the height of the table to account for the header
+        self.page_height = (inner_area.height as usize).saturating_sub(1).max(1);
 
-            lines.push(lesson.draw(selected, is_editing, text_box));
-        }
+        let rows = self.lessons.iter().enumerate().map(|(i, lesson)| {
+            lesson.get_row(
+                &columns,
+                i == self.ptr,
+                self.marked.contains(&lesson.lesson.id),
+            )
+        });
+        let widths = columns.iter().map(|column| {
+            let width = self.get_header_name(column).chars().count() as u16;
+            match column {
+                SortColumn::Name => Constraint::Min(width.max(get_width(column))),
+                _ => Constraint::Length(width.max(get_width(column))),
+            }
+        });
+        let header = Row::new(
+            columns
+                .iter()
+                .map(|column| self.get_header_cell(column))
+                .collect::<Vec<Cell>>(),
+        );
+
+        let table = Table::new(rows, widths).header(header).block(block);
+
+        // The table scrolls as little as possible to keep the selected lesson visible
+        self.table_state.select(if self.lessons.is_empty() {
+            None
+        } else {
+            Some(self.ptr)
+        });
+        f.render_stateful_widget(table, area, &mut self.table_state);
+
+        // Render the scrollbar on the right border next to the lessons, below the top border and
+        // the header and above the bottom border
+        if self.lessons.len() > self.page_height {
+            let mut scrollbar_state = ScrollbarState::new(self.lessons.len())
+                .viewport_content_length(self.page_height)
+                .position(self.ptr);
+            let scrollbar_area = Rect {
+                y: area.y + 2,
+                height: area.height.saturating_sub(3),
+                ..area
+            };
+            f.render_stateful_widget(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                scrollbar_area,
+                &mut scrollbar_state,
+            );
+        };
 
-        // Remove the number of lines calculated in lines_to_subtract
-        if lines_to_subtract > 0 {
-            lines.drain(0..lines_to_subtract);
+        // Render the confirmation on top of the lessons
+        if let (SubState::Delete(_) | SubState::Bulk, Some(confirm_dialog)) =
+            (sub_state, &self.confirm_dialog)
+        {
+            confirm_dialog.render(f, area);
         };
 
-        f.render_widget(Paragraph::new(lines).block(block), area);
+        if let (SubState::Bulk, Some(lesson_picker)) = (sub_state, &mut self.lesson_picker) {
+            lesson_picker.render(f, area, &self.catalogue);
+        };
+        if let (SubState::Edit(_), Some(lesson_form)) = (sub_state, &mut self.lesson_form) {
+            lesson_form.render(f, area, &self.catalogue);
+        };
     }
 
     pub fn handle_events(
@@ -76,11 +198,92 @@ impl LessonsWidget {
         event: Event,
         sub_state: &SubState,
         conn: &Connection,
+        session: &mut Session,
         text_box: &mut TextBox,
     ) -> EventResult {
         match event {
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => match sub_state {
                 SubState::None => match key_event.code {
+                    KeyCode::Up | KeyCode::Down
+                        if key_event.modifiers.contains(KeyModifiers::SHIFT) =>
+                    {
+                        // Mark the lessons from the selected one to the next one
+                        if let Some(lesson) = self.lessons.get(self.ptr) {
+                            self.marked.insert(lesson.lesson.id);
+                        };
+                        if key_event.code == KeyCode::Up {
+                            self.ptr = self.ptr.saturating_sub(1);
+                        } else if self.ptr + 1 < self.lessons.len() {
+                            self.ptr += 1;
+                        };
+                        if let Some(lesson) = self.lessons.get(self.ptr) {
+                            self.marked.insert(lesson.lesson.id);
+                        };
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char(' ') => {
+                        // Toggle the mark of the selected lesson and go to the next one
+                        if let Some(lesson) = self.lessons.get(self.ptr) {
+                            let id = lesson.lesson.id;
+                            if !self.marked.remove(&id) {
+                                self.marked.insert(id);
+                            };
+                        };
+                        if self.ptr + 1 < self.lessons.len() {
+                            self.ptr += 1;
+                        };
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('a') => {
+                        // Mark every lesson that matches the filter or unmark them if they are
+                        let ids = self
+                            .lessons
+                            .iter()
+                            .map(|lesson| lesson.lesson.id)
+                            .collect::<Vec<usize>>();
+                        if ids.iter().all(|id| self.marked.contains(id)) {
+                            for id in ids {
+                                self.marked.remove(&id);
+                            }
+                        } else {
+                            self.marked.extend(ids);
+                        };
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('#') => {
+                        let number = self
+                            .lessons
+                            .get(self.ptr)
+                            .and_then(|lesson| lesson.lesson.number);
+                        self.lesson_picker = Some(LessonPicker::new(&self.catalogue, number));
+                        self.start_bulk_input(BulkInput::Lesson, text_box)
+                    }
+                    KeyCode::Char('t') => {
+                        self.start_bulk_input(BulkInput::ShiftTimestamps, text_box)
+                    }
+                    KeyCode::Char('d') => {
+                        self.start_bulk_input(BulkInput::ScaleDurations, text_box)
+                    }
+                    KeyCode::Delete if !self.marked.is_empty() => {
+                        // Ask before deleting the marked lessons
+                        let lines = self
+                            .lessons
+                            .iter()
+                            .filter(|lesson| self.marked.contains(&lesson.lesson.id))
+                            .take(5)
+                            .map(|lesson| Line::from_iter(lesson.lesson.get_line()))
+                            .collect::<Vec<Line>>();
+                        self.confirm_dialog = Some(ConfirmDialog::new(
+                            &format!("Delete {} lessons?", self.marked.len()),
+                            lines,
+                        ));
+
+                        self.bulk_input = Some(BulkInput::Delete);
+                        EventResult::SetSubState(SubState::Bulk)
+                    }
                     KeyCode::Up => {
                         // Check if there are any lessons and move the pointer up
                         if self.ptr > 0 {
@@ -89,6 +292,23 @@ impl LessonsWidget {
 
                         EventResult::None(ResultError::None)
                     }
+                    KeyCode::PageUp => {
+                        self.ptr = self.ptr.saturating_sub(self.page_height);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::PageDown => {
+                        self.ptr =
+                            (self.ptr + self.page_height).min(self.lessons.len().saturating_sub(1));
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Home => {
+                        self.ptr = 0;
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::End => {
+                        self.move_ptr_to_last();
+                        EventResult::None(ResultError::None)
+                    }
                     KeyCode::Down => {
                         if self.lessons.is_empty() {
                             // Check if there are any lessons
@@ -102,12 +322,45 @@ impl LessonsWidget {
 
                         EventResult::None(ResultError::None)
                     }
+                    KeyCode::Left => {
+                        self.sort.column = self.sort.column.previous();
+                        self.sort_lessons();
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Right => {
+                        self.sort.column = self.sort.column.next();
+                        self.sort_lessons();
+
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('/') => {
+                        // Edit the filter in the text box
+                        let query = self.filter.get_query();
+                        text_box.reset();
+                        text_box.set_max_len(None);
+                        text_box.set_buf(query);
+                        match text_box.set_ptr(query.chars().count()) {
+                            Ok(_) => EventResult::SetSubState(SubState::Search),
+                            Err(_) => EventResult::None(ResultError::TextBoxError),
+                        }
+                    }
+                    KeyCode::Char('r') => {
+                        // Reverse the order of the lessons
+                        self.sort.descending = !self.sort.descending;
+                        self.sort_lessons();
+
+                        EventResult::None(ResultError::None)
+                    }
                     KeyCode::Enter => {
                         if self.lessons.is_empty() {
                             return EventResult::None(ResultError::NoLessons);
                         };
 
-                        EventResult::SetSubState(SubState::Edit(self.lessons[self.ptr].lesson.id))
+                        let lesson = &self.lessons[self.ptr].lesson;
+                        self.lesson_form = Some(LessonForm::new(lesson, session.validation_rules));
+
+                        EventResult::SetSubState(SubState::Edit(lesson.id))
                     }
                     KeyCode::Delete => {
                         // Check if there are any lessons
@@ -115,85 +368,431 @@ impl LessonsWidget {
                             return EventResult::None(ResultError::NoLessons);
                         };
 
-                        // Delete the lesson from the database
-                        match tipp10::delete_lesson(conn, self.lessons[self.ptr].lesson.id) {
-                            Ok(_) => (),
-                            Err(e) => {
-                                error!("Could not delete lesson from database! Error: {}", e);
-                                return EventResult::None(ResultError::SQLite);
-                            }
+                        // Ask before deleting the lesson
+                        let lesson = &self.lessons[self.ptr].lesson;
+                        self.confirm_dialog = Some(ConfirmDialog::new(
+                            "Delete lesson?",
+                            vec![Line::from_iter(lesson.get_line())],
+                        ));
+
+                        EventResult::SetSubState(SubState::Delete(lesson.id))
+                    }
+                    KeyCode::Char('n') => {
+                        // The lesson is only appended when the form is saved
+                        let lesson = Lesson::new(
+                            0,
+                            self.catalogue.get_default(),
+                            tipp10::get_timestamp(),
+                            600,
+                            0,
+                            0,
+                        );
+                        self.lesson_form = Some(LessonForm::for_new_lesson(
+                            &lesson,
+                            session.validation_rules,
+                        ));
+                        EventResult::SetSubState(SubState::Edit(lesson.id))
+                    }
+                    KeyCode::Char('u') => {
+                        self.update_lessons(conn, session);
+
+                        if self.lessons.is_empty() {
+                            return EventResult::None(ResultError::NoLessons);
                         };
 
-                        self.update_lessons(conn);
+                        if self.ptr > self.lessons.len() - 1 {
+                            self.move_ptr_to_last();
+                        };
 
-                        if self.ptr >= self.lessons.len() && !self.lessons.is_empty() {
-                            self.ptr = self.lessons.len() - 1;
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('z') => {
+                        let id = match tipp10::undo(conn, session) {
+                            Ok(Some(id)) => id,
+                            Ok(None) => return EventResult::None(ResultError::NothingToUndo),
+                            Err(e) => {
+                                error!("Could not undo the last change! Error: {}", e);
+                                return EventResult::None(ResultError::SQLite);
+                            }
                         };
 
+                        self.update_lessons_and_move_ptr_to_id(conn, session, id);
                         EventResult::None(ResultError::None)
                     }
-                    KeyCode::Char('n') => {
-                        let id = match tipp10::append_lesson(
-                            conn,
-                            18,
-                            0,
-                            0,
-                            600,
-                            tipp10::get_timestamp(),
-                        ) {
-                            Ok(lesson) => lesson,
+                    KeyCode::Char('y') => {
+                        let id = match tipp10::redo(conn, session) {
+                            Ok(Some(id)) => id,
+                            Ok(None) => return EventResult::None(ResultError::NothingToRedo),
                             Err(e) => {
-                                error!("Could not append lesson to database! Error: {}", e);
+                                error!("Could not redo the last change! Error: {}", e);
                                 return EventResult::None(ResultError::SQLite);
                             }
                         };
 
-                        self.update_lessons_and_move_ptr_to_last(conn);
-                        EventResult::SetSubState(SubState::Edit(id))
+                        self.update_lessons_and_move_ptr_to_id(conn, session, id);
+                        EventResult::None(ResultError::None)
                     }
-                    KeyCode::Char('u') => {
-                        self.update_lessons(conn);
+                    KeyCode::Char('l') => {
+                        // Choose the language of the lesson names of new or changed lessons
+                        session.language = Language::next(session.language);
+                        self.catalogue = LessonCatalogue::load(conn, session.language);
 
-                        if self.lessons.is_empty() {
-                            return EventResult::None(ResultError::NoLessons);
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Char('b') => EventResult::SetState(State::Backups),
+                    KeyCode::Char('s') => EventResult::SetState(State::Statistics),
+                    KeyCode::Char('c') => EventResult::SetState(State::Calendar),
+                    KeyCode::Esc => {
+                        // Clear the marks and the filter before exiting
+                        if !self.marked.is_empty() {
+                            self.marked.clear();
+                            return EventResult::None(ResultError::None);
                         };
-
-                        if self.ptr > self.lessons.len() - 1 {
-                            self.move_ptr_to_last();
+                        if self.filter.is_empty() {
+                            return EventResult::Exit;
                         };
 
+                        self.filter = LessonFilter::default();
+                        self.apply_filter();
+
                         EventResult::None(ResultError::None)
                     }
-                    KeyCode::Esc => EventResult::Exit,
                     _ => EventResult::None(ResultError::None),
                 },
-                SubState::Edit(id) => {
-                    // Handle events for the lesson being edited
-                    for lesson in &mut self.lessons {
-                        if lesson.lesson.id == *id {
-                            return lesson.handle_events(event, conn, text_box);
+                SubState::Bulk => self.handle_bulk_events(event, conn, session, text_box),
+                SubState::Search => match key_event.code {
+                    KeyCode::Enter => {
+                        // Keep the filter
+                        text_box.reset();
+                        EventResult::SetSubState(SubState::None)
+                    }
+                    KeyCode::Esc => {
+                        // Clear the filter
+                        text_box.reset();
+                        self.filter = LessonFilter::default();
+                        self.apply_filter();
+
+                        EventResult::SetSubState(SubState::None)
+                    }
+                    _ => {
+                        let event_result = match text_box.handle_events(&event) {
+                            Ok(event_result) => event_result,
+                            Err(_) => EventResult::None(ResultError::TextBoxError),
                         };
+
+                        // Filter the lessons while typing
+                        self.filter = LessonFilter::new(text_box.get_buffer_ref());
+                        self.apply_filter();
+
+                        event_result
                     }
+                },
+                SubState::Delete(id) => {
+                    let event_result = match &self.confirm_dialog {
+                        Some(confirm_dialog) => confirm_dialog.handle_events(&event),
+                        None => EventResult::Cancel,
+                    };
+
+                    match event_result {
+                        EventResult::Submit => {
+                            // Delete the lesson from the database. The dialog stays open if it fails
+                            match tipp10::delete_lesson(conn, session, *id) {
+                                Ok(_) => (),
+                                Err(e) => {
+                                    error!("Could not delete lesson from database! Error: {}", e);
+                                    return EventResult::None(ResultError::SQLite);
+                                }
+                            };
+
+                            self.confirm_dialog = None;
+                            self.update_lessons(conn, session);
+
+                            if self.ptr >= self.lessons.len() && !self.lessons.is_empty() {
+                                self.ptr = self.lessons.len() - 1;
+                            };
 
-                    EventResult::SetSubState(SubState::None)
+                            EventResult::SetSubState(SubState::None)
+                        }
+                        EventResult::Cancel => {
+                            self.confirm_dialog = None;
+                            EventResult::SetSubState(SubState::None)
+                        }
+                        event_result => event_result,
+                    }
+                }
+                SubState::Edit(_) => {
+                    // Handle events for the form of the lesson being edited
+                    let lesson_form = match &mut self.lesson_form {
+                        Some(lesson_form) => lesson_form,
+                        None => return EventResult::SetSubState(SubState::None),
+                    };
+
+                    match lesson_form.handle_events(&event, conn, session, &self.catalogue) {
+                        EventResult::Submit => {
+                            // The changed lesson may belong somewhere else in the order or not
+                            // match the filter anymore
+                            let id = lesson_form.get_id();
+                            self.notice = lesson_form.get_warning();
+                            // Clear the filter so a new lesson is shown
+                            if lesson_form.is_new() {
+                                self.filter = LessonFilter::default();
+                            };
+                            self.lesson_form = None;
+                            self.update_lessons(conn, session);
+                            self.move_ptr_to_id(id);
+                            if self.ptr >= self.lessons.len() {
+                                self.move_ptr_to_last();
+                            };
+
+                            EventResult::SetSubState(SubState::None)
+                        }
+                        EventResult::Cancel => {
+                            self.lesson_form = None;
+                            EventResult::SetSubState(SubState::None)
+                        }
+                        event_result => event_result,
+                    }
                 }
             },
             _ => EventResult::None(ResultError::None),
         }
     }
 
-    /// Get the lessons from the database and return them as a vector of LessonWidget
-    fn get_lessons(&mut self, conn: &Connection) -> Vec<LessonWidget> {
-        let lessons_save_data = tipp10::get_lessons(conn).expect("Could not get lessons!");
-        lessons_save_data
+    /// Handle the events of the confirmation or the input of a bulk action
+    fn handle_bulk_events(
+        &mut self,
+        event: Event,
+        conn: &Connection,
+        session: &mut Session,
+        text_box: &mut TextBox,
+    ) -> EventResult {
+        let bulk_input = match self.bulk_input {
+            Some(bulk_input) => bulk_input,
+            None => return EventResult::SetSubState(SubState::None),
+        };
+
+        let event_result = if let BulkInput::Delete = bulk_input {
+            match &self.confirm_dialog {
+                Some(confirm_dialog) => confirm_dialog.handle_events(&event),
+                None => EventResult::Cancel,
+            }
+        } else if let Some(lesson_picker) = &mut self.lesson_picker {
+            lesson_picker.handle_events(&event, &self.catalogue)
+        } else {
+            match event {
+                Event::Key(key_event) if key_event.code == KeyCode::Enter => EventResult::Submit,
+                Event::Key(key_event) if key_event.code == KeyCode::Esc => EventResult::Cancel,
+                _ => match text_box.handle_events(&event) {
+                    Ok(_) => EventResult::None(ResultError::None),
+                    Err(_) => EventResult::None(ResultError::TextBoxError),
+                },
+            }
+        };
+
+        match event_result {
+            EventResult::Submit => {
+                let input = match &self.lesson_picker {
+                    Some(lesson_picker) => lesson_picker
+                        .get_selected()
+                        .map_or(String::new(), |number| number.to_string()),
+                    None => text_box.get_buffer(),
+                };
+                let action = match bulk_input.parse(&input, &self.catalogue) {
+                    Some(action) => action,
+                    None => return EventResult::None(ResultError::WrongInput),
+                };
+
+                // Apply the action to every lesson at once. The input stays open if it fails
+                let ids = self.get_bulk_ids();
+                let count = match bulk::apply_bulk_action(conn, session, &ids, &action) {
+                    Ok(count) => count,
+                    Err(e @ SQLiteError::InvalidLesson(_)) => {
+                        return EventResult::None(ResultError::Invalid(e.to_string()));
+                    }
+                    Err(e) => {
+                        error!("Could not apply the bulk action! Error: {}", e);
+                        return EventResult::None(ResultError::SQLite);
+                    }
+                };
+
+                let selected = self.lessons.get(self.ptr).map(|lesson| lesson.lesson.id);
+                self.marked.clear();
+                self.update_lessons(conn, session);
+                match selected {
+                    Some(id) if action != BulkAction::Delete => self.move_ptr_to_id(id),
+                    _ => (),
+                };
+                if self.ptr >= self.lessons.len() {
+                    self.move_ptr_to_last();
+                };
+
+                self.notice = Some(ResultError::LessonsChanged(count));
+                self.close_bulk_input(text_box)
+            }
+            EventResult::Cancel => self.close_bulk_input(text_box),
+            event_result => event_result,
+        }
+    }
+
+    /// Open the input of a bulk action
+    fn start_bulk_input(&mut self, bulk_input: BulkInput, text_box: &mut TextBox) -> EventResult {
+        if self.lessons.is_empty() {
+            return EventResult::None(ResultError::NoLessons);
+        };
+
+        text_box.reset();
+        text_box.set_max_len(None);
+        self.bulk_input = Some(bulk_input);
+
+        EventResult::SetSubState(SubState::Bulk)
+    }
+
+    /// Close the confirmation or the input of a bulk action
+    fn close_bulk_input(&mut self, text_box: &mut TextBox) -> EventResult {
+        self.bulk_input = None;
+        self.confirm_dialog = None;
+        self.lesson_picker = None;
+        text_box.reset();
+
+        EventResult::SetSubState(SubState::None)
+    }
+
+    /// Get the IDs of the lessons a bulk action is applied to. These are the marked lessons or the
+    /// selected lesson if none is marked.
+    pub fn get_bulk_ids(&self) -> Vec<usize> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().copied().collect();
+        };
+
+        self.lessons
+            .get(self.ptr)
+            .map(|lesson| vec![lesson.lesson.id])
+            .unwrap_or_default()
+    }
+
+    /// Get the example of the input of the open bulk action
+    pub fn get_bulk_example(&self) -> Option<&'static str> {
+        self.bulk_input
+            .and_then(|bulk_input| bulk_input.get_example())
+    }
+
+    /// Check if the lesson of the marked lessons is chosen from the lesson picker
+    pub fn is_choosing_bulk_lesson(&self) -> bool {
+        self.bulk_input == Some(BulkInput::Lesson)
+    }
+
+    /// Take the message about the last change, so it is only shown once
+    pub fn take_notice(&mut self) -> Option<ResultError> {
+        self.notice.take()
+    }
+
+    /// Get the title showing the language of the lesson names of new or changed lessons.
+    fn get_language_title(&self) -> Line<'static> {
+        let language = match self.catalogue.get_language() {
+            Some(language) => language.get_name(),
+            None => "Lesson list",
+        };
+
+        Line::from(format!(" Lesson names: {} ", language)).right_aligned()
+    }
+
+    /// Get the name of a column in the header. The column the lessons are sorted by shows the
+    /// direction of the order.
+    fn get_header_name(&self, column: &SortColumn) -> String {
+        if *column == self.sort.column {
+            format!("{} {}", column.get_name(), self.sort.get_symbol())
+        } else {
+            column.get_name().to_string()
+        }
+    }
+
+    /// Get the cell of a column in the header. The column the lessons are sorted by is highlighted.
+    fn get_header_cell(&self, column: &SortColumn) -> Cell<'static> {
+        let cell = Cell::from(self.get_header_name(column)).bold();
+        if *column == self.sort.column {
+            cell.fg(Color::Yellow)
+        } else {
+            cell
+        }
+    }
+
+    /// Get the number of lessons that match the filter and the number of all lessons. None if
+    /// there is no filter.
+    pub fn get_match_count(&self) -> Option<(usize, usize)> {
+        if self.filter.is_empty() {
+            return None;
+        };
+
+        Some((self.lessons.len(), self.all_lessons.len()))
+    }
+
+    /// Get the lessons that match the filter in the sorted order
+    fn get_lessons(&self) -> Vec<LessonWidget> {
+        let mut lessons = self
+            .all_lessons
             .iter()
+            .filter(|lesson| self.filter.matches(lesson))
             .map(|lesson| LessonWidget::new(lesson.clone()))
-            .collect()
+            .collect::<Vec<LessonWidget>>();
+
+        let sort = self.sort;
+        lessons.sort_by(|a, b| sort.compare(&a.lesson, &b.lesson));
+
+        lessons
     }
 
-    /// Update the lessons from the database
-    pub fn update_lessons(&mut self, conn: &Connection) {
-        self.lessons = self.get_lessons(conn);
+    /// Update the lessons and the catalogue from the database. The marks are cleared if lessons
+    /// were added or removed, because their IDs may have changed.
+    pub fn update_lessons(&mut self, conn: &Connection, session: &Session) {
+        let old_ids = self
+            .all_lessons
+            .iter()
+            .map(|lesson| lesson.id)
+            .collect::<Vec<usize>>();
+
+        self.catalogue = LessonCatalogue::load(conn, session.language);
+        self.all_lessons = tipp10::get_lessons(conn).expect("Could not get lessons!");
+        self.lessons = self.get_lessons();
+
+        if !self.all_lessons.iter().map(|lesson| lesson.id).eq(old_ids) {
+            self.marked.clear();
+        };
+    }
+
+    /// Filter the lessons again and keep the selected lesson selected if it still matches
+    fn apply_filter(&mut self) {
+        let selected = self.lessons.get(self.ptr).map(|lesson| lesson.lesson.id);
+
+        self.lessons = self.get_lessons();
+
+        self.ptr = 0;
+        if let Some(id) = selected {
+            self.move_ptr_to_id(id);
+        };
+    }
+
+    /// Sort the lessons again and keep the selected lesson selected
+    fn sort_lessons(&mut self) {
+        let selected = self.lessons.get(self.ptr).map(|lesson| lesson.lesson.id);
+
+        let sort = self.sort;
+        self.lessons
+            .sort_by(|a, b| sort.compare(&a.lesson, &b.lesson));
+
+        if let Some(id) = selected {
+            self.move_ptr_to_id(id);
+        };
+    }
+
+    /// Move the pointer to the lesson with the ID. The pointer stays if there is no such lesson
+    fn move_ptr_to_id(&mut self, id: usize) {
+        if let Some(ptr) = self
+            .lessons
+            .iter()
+            .position(|lesson| lesson.lesson.id == id)
+        {
+            self.ptr = ptr;
+        };
     }
 
     /// Move the pointer to the last lesson
@@ -204,8 +803,133 @@ impl LessonsWidget {
     }
 
     /// Update the lessons from the database and move the pointer to the last lesson
-    pub fn update_lessons_and_move_ptr_to_last(&mut self, conn: &Connection) {
-        self.update_lessons(conn);
+    pub fn update_lessons_and_move_ptr_to_last(&mut self, conn: &Connection, session: &Session) {
+        self.update_lessons(conn, session);
         self.move_ptr_to_last();
     }
+
+    /// Update the lessons from the database and move the pointer to the lesson with the ID or the
+    /// closest lesson if it does not exist anymore
+    pub fn update_lessons_and_move_ptr_to_id(
+        &mut self,
+        conn: &Connection,
+        session: &Session,
+        id: usize,
+    ) {
+        self.update_lessons(conn, session);
+
+        // The closest lesson is the one with the next higher ID, wherever it is in the order
+        let closest = self
+            .lessons
+            .iter()
+            .filter(|lesson| lesson.lesson.id >= id)
+            .min_by_key(|lesson| lesson.lesson.id)
+            .map(|lesson| lesson.lesson.id);
+
+        match closest {
+            Some(id) => self.move_ptr_to_id(id),
+            None => self.move_ptr_to_last(),
+        };
+    }
+}
+
+/// The columns that are hidden one after another when the table does not fit into the terminal.
+const HIDDEN_FIRST: [SortColumn; 10] = [
+    SortColumn::Tokens,
+    SortColumn::Lesson,
+    SortColumn::Wpm,
+    SortColumn::Type,
+    SortColumn::Points,
+    SortColumn::Id,
+    SortColumn::Duration,
+    SortColumn::ErrorRate,
+    SortColumn::Strokes,
+    SortColumn::Errors,
+];
+
+/// Get the width of the values of a column. The name is cut if it is longer.
+fn get_width(column: &SortColumn) -> u16 {
+    match column {
+        SortColumn::Id => 4,
+        SortColumn::Lesson => 6,
+        SortColumn::Name => 20,
+        SortColumn::Timestamp => 14,
+        SortColumn::Duration => 8,
+        SortColumn::Strokes => 7,
+        SortColumn::Errors => 6,
+        SortColumn::Tokens => 6,
+        SortColumn::Type => 9,
+        SortColumn::Cpm => 6,
+        SortColumn::Wpm => 5,
+        SortColumn::ErrorRate => 10,
+        SortColumn::Points => 6,
+    }
+}
+
+/// Get the columns that fit into the width. The space for the sort symbol is kept free.
+fn get_columns(width: u16) -> Vec<SortColumn> {
+    let mut columns = SortColumn::ALL.to_vec();
+    let needed = |columns: &[SortColumn]| {
+        columns
+            .iter()
+            .map(|column| get_width(column).max(column.get_name().len() as u16 + 2) + 1)
+            .sum::<u16>()
+    };
+
+    for hidden in HIDDEN_FIRST {
+        if needed(&columns) <= width {
+            break;
+        };
+        columns.retain(|column| *column != hidden);
+    }
+
+    columns
+}
+
+/// The bulk actions that can be applied to the marked lessons.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BulkInput {
+    Delete,
+    Lesson,
+    ShiftTimestamps,
+    ScaleDurations,
+}
+impl BulkInput {
+    /// Get the name of the bulk action.
+    fn get_name(&self) -> &'static str {
+        match self {
+            BulkInput::Delete => "Delete",
+            BulkInput::Lesson => "Lesson",
+            BulkInput::ShiftTimestamps => "Shift timestamps",
+            BulkInput::ScaleDurations => "Scale durations",
+        }
+    }
+
+    /// Get the example of the input of the bulk action. None if the action has no typed input.
+    fn get_example(&self) -> Option<&'static str> {
+        match self {
+            BulkInput::Delete => Some(""),
+            // The lesson is chosen from the lesson picker
+            BulkInput::Lesson => None,
+            BulkInput::ShiftTimestamps => Some("-1h30m, +2d"),
+            BulkInput::ScaleDurations => Some("1.5, 50%"),
+        }
+    }
+
+    /// Parse the input into the bulk action. None if the input is not valid.
+    fn parse(&self, input: &str, catalogue: &LessonCatalogue) -> Option<BulkAction> {
+        match self {
+            BulkInput::Delete => Some(BulkAction::Delete),
+            BulkInput::Lesson => {
+                let number = input.trim().parse::<usize>().ok()?;
+                Some(BulkAction::SetLesson(catalogue.get(number)?.clone()))
+            }
+            BulkInput::ShiftTimestamps => {
+                Some(BulkAction::ShiftTimestamps(duration::parse_offset(input)?))
+            }
+            BulkInput::ScaleDurations => {
+                Some(BulkAction::ScaleDurations(bulk::parse_factor(input)?))
+            }
+        }
+    }
 }
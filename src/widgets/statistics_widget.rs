@@ -0,0 +1,298 @@
+use chrono::{DateTime, Local};
+use log::error;
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEventKind},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{
+        Axis, Bar, BarChart, BarGroup, Block, BorderType, Borders, Chart, Dataset, GraphType,
+        Paragraph, Sparkline,
+    },
+    Frame,
+};
+use rusqlite::Connection;
+
+use crate::{
+    state::{State, SubState},
+    tipp10::{
+        self,
+        statistics::{self, DateRange},
+        Lesson,
+    },
+    tipp10w::{EventResult, ResultError},
+};
+
+// This widget is responsible for showing the progress of the lessons in charts
+pub struct StatisticsWidget {
+    pub lessons: Vec<Lesson>,
+    pub range: DateRange,
+}
+impl Default for StatisticsWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatisticsWidget {
+    pub fn new() -> Self {
+        Self {
+            lessons: Vec::new(),
+            range: DateRange::default(),
+        }
+    }
+
+    /// Load the lessons from the database.
+    pub fn update_lessons(&mut self, conn: &Connection) -> EventResult {
+        self.lessons = match tipp10::get_lessons(conn) {
+            Ok(lessons) => lessons,
+            Err(e) => {
+                error!("Could not get the lessons for the statistics! Error: {}", e);
+                return EventResult::None(ResultError::SQLite);
+            }
+        };
+
+        if self.lessons.is_empty() {
+            return EventResult::None(ResultError::NoLessons);
+        };
+
+        EventResult::None(ResultError::None)
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        let lessons = self.range.filter(&self.lessons, &Local::now());
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .title(" Statistics ")
+            .title(Line::from(format!(" {} ", self.range.get_name())).right_aligned());
+
+        if lessons.is_empty() {
+            f.render_widget(
+                Paragraph::new("No lessons in this date range!")
+                    .alignment(Alignment::Center)
+                    .block(block),
+                area,
+            );
+            return;
+        };
+
+        let inner_area = block.inner(area);
+        f.render_widget(block, area);
+
+        // Create a vertical layout with the summary, the charts over time and the breakdowns
+        let chunks_vertical = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ])
+            .split(inner_area);
+        let chunks_over_time = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+            .split(chunks_vertical[1]);
+        let chunks_breakdown = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+            .split(chunks_vertical[2]);
+
+        f.render_widget(get_summary(&lessons), chunks_vertical[0]);
+        render_chart(
+            f,
+            chunks_over_time[0],
+            " CPM over time ",
+            &statistics::get_time_series(&lessons, Lesson::get_cpm),
+            Color::Yellow,
+        );
+        render_chart(
+            f,
+            chunks_over_time[1],
+            " Error rate in % over time ",
+            &statistics::get_time_series(&lessons, Lesson::get_error_rate),
+            Color::Red,
+        );
+        render_lesson_bar_chart(f, chunks_breakdown[0], &lessons);
+        render_sparkline(f, chunks_breakdown[1], &lessons);
+    }
+
+    /// `Left` and `Right` change the date range and `Esc` goes back to the lessons.
+    pub fn handle_events(&mut self, event: &Event) -> EventResult {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                match key_event.code {
+                    KeyCode::Left => {
+                        self.range = self.range.previous();
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Right => {
+                        self.range = self.range.next();
+                        EventResult::None(ResultError::None)
+                    }
+                    KeyCode::Esc => EventResult::SetState(State::Menu(SubState::None)),
+                    _ => EventResult::None(ResultError::None),
+                }
+            }
+            _ => EventResult::None(ResultError::None),
+        }
+    }
+}
+
+/// Get the line with the totals and averages of the lessons.
+fn get_summary(lessons: &[&Lesson]) -> Line<'static> {
+    let seconds = lessons.iter().map(|lesson| lesson.duration).sum::<usize>();
+    let best_cpm = lessons
+        .iter()
+        .map(|lesson| lesson.get_cpm())
+        .fold(0.0, f64::max);
+
+    Line::from_iter([
+        Span::from("Lessons: "),
+        Span::from(lessons.len().to_string()).fg(Color::Yellow),
+        Span::from(" | Time: "),
+        Span::from(format!("{}:{:02} h", seconds / 3600, seconds % 3600 / 60)).fg(Color::Yellow),
+        Span::from(" | Average CPM: "),
+        Span::from(format!(
+            "{:.1}",
+            statistics::get_average(lessons, Lesson::get_cpm)
+        ))
+        .fg(Color::Yellow),
+        Span::from(" | Best CPM: "),
+        Span::from(format!("{:.1}", best_cpm)).fg(Color::Yellow),
+        Span::from(" | Average error rate: "),
+        Span::from(format!(
+            "{:.2}%",
+            statistics::get_average(lessons, Lesson::get_error_rate)
+        ))
+        .fg(Color::Yellow),
+    ])
+    .alignment(Alignment::Center)
+}
+
+/// Render a line chart of a metric over time.
+fn render_chart(f: &mut Frame, area: Rect, title: &str, data: &[(f64, f64)], color: Color) {
+    let (min_x, max_x) = data
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (x, _)| {
+            (min.min(*x), max.max(*x))
+        });
+    // Widen the x axis by a day if there is only one point in time
+    let max_x = if max_x > min_x {
+        max_x
+    } else {
+        min_x + 86400.0
+    };
+    let max_y = data.iter().map(|(_, y)| *y).fold(0.0, f64::max).max(1.0) * 1.1;
+
+    let date_label = |x: f64| match DateTime::from_timestamp(x as i64, 0) {
+        Some(datetime) => datetime
+            .with_timezone(&Local)
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => String::new(),
+    };
+
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data);
+
+    let chart = Chart::new(vec![dataset])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title.to_string()),
+        )
+        .x_axis(
+            Axis::default()
+                .bounds([min_x, max_x])
+                .labels(vec![date_label(min_x), date_label(max_x)]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_y])
+                .labels(vec!["0".to_string(), format!("{:.0}", max_y)]),
+        );
+
+    f.render_widget(chart, area);
+}
+
+/// Render the average CPM of every lesson number as a bar chart.
+fn render_lesson_bar_chart(f: &mut Frame, area: Rect, lessons: &[&Lesson]) {
+    let bars = statistics::get_lesson_statistics(lessons)
+        .iter()
+        .map(|statistics| {
+            let label = match statistics.number {
+                Some(number) => format!("L{}", number),
+                None => "?".to_string(),
+            };
+
+            Bar::default()
+                .value(statistics.average_cpm.round() as u64)
+                .label(Line::from(label))
+        })
+        .collect::<Vec<Bar>>();
+
+    let bar_chart = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Average CPM per lesson "),
+        )
+        .bar_width(4)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Yellow))
+        .data(BarGroup::default().bars(&bars));
+
+    f.render_widget(bar_chart, area);
+}
+
+/// Render the CPM of the latest lessons that fit into the area as a sparkline.
+fn render_sparkline(f: &mut Frame, area: Rect, lessons: &[&Lesson]) {
+    // Subtract 2 from the width to account for the border
+    let width = area.width.saturating_sub(2) as usize;
+    let data = lessons
+        .iter()
+        .skip(lessons.len().saturating_sub(width))
+        .map(|lesson| lesson.get_cpm().round() as u64)
+        .collect::<Vec<u64>>();
+
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" CPM of the latest lessons "),
+        )
+        .style(Style::default().fg(Color::Green))
+        .data(&data);
+
+    f.render_widget(sparkline, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
+
+    use super::*;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn test_handle_events() {
+        let mut widget = StatisticsWidget::new();
+        widget.handle_events(&key(KeyCode::Left));
+        assert_eq!(widget.range, DateRange::Year);
+        widget.handle_events(&key(KeyCode::Right));
+        assert_eq!(widget.range, DateRange::All);
+        assert_eq!(
+            widget.handle_events(&key(KeyCode::Esc)),
+            EventResult::SetState(State::Menu(SubState::None))
+        );
+    }
+}
@@ -37,6 +37,11 @@ impl TextBox {
     }
 
     pub fn draw(&self) -> Paragraph<'_> {
+        Paragraph::new(self.get_line())
+    }
+
+    /// Get the buffer as a line with the cursor highlighted.
+    pub fn get_line(&self) -> Line<'static> {
         let mut buf = self.buf.clone();
         // Add space to end so cursor can be at the end of the buffer
         buf.push(' ');
@@ -56,7 +61,7 @@ impl TextBox {
             };
         }
 
-        Paragraph::new(Line::from_iter(spans))
+        Line::from_iter(spans)
     }
 
     pub fn handle_events(&mut self, event: &Event) -> io::Result<EventResult> {